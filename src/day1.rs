@@ -1,17 +1,17 @@
-use crate::util;
+use crate::util::{Counter, Input};
 
 pub fn run(example: bool) {
-    let data = if example {
+    let data = Input::new(if example {
         include_str!("../data/example/day1.1.txt")
     } else {
         include_str!("../data/day1.1.txt")
-    };
+    });
 
     tracing::info!("day 1 part 1{}", if example { " example" } else { "" });
     tracing::info!(
         "day 1 part 1{} result: {}",
         if example { " example" } else { "" },
-        part1(data),
+        part1(&data),
     );
 
     if example {
@@ -22,27 +22,25 @@ pub fn run(example: bool) {
     tracing::info!(
         "day 1 part 2{} result: {}",
         if example { " example" } else { "" },
-        part2(data), // same data
+        part2(&data), // same data
     );
 }
 
-fn left_right(data: &str) -> Option<(Vec<u64>, Vec<u64>)> {
-
+fn left_right(data: &Input) -> Option<(Vec<u64>, Vec<u64>)> {
     let mut left = Vec::new();
     let mut right = Vec::new();
 
-    for (line_num, line) in data.lines().enumerate() {
-        let [left_str, right_str] = &line.split_whitespace().collect::<Vec<&str>>()[..] else {
-            tracing::error!("more than two words on line {}: {:?}", line_num, line);
-            return None;
+    for line in data.lines() {
+        let numbers = match line.numbers::<u64>() {
+            Ok(numbers) => numbers,
+            Err(err) => {
+                tracing::error!("{}", err);
+                return None;
+            }
         };
 
-        let Ok(left_num) = left_str.parse::<u64>() else {
-            tracing::error!("not a number on line {}: {:?}", line_num, left_str);
-            return None;
-        };
-        let Ok(right_num) = right_str.parse::<u64>() else {
-            tracing::error!("not a number on line {}: {:?}", line_num, right_str);
+        let [left_num, right_num] = numbers[..] else {
+            tracing::error!("not two numbers on line {}: {:?}", line.number, line.text);
             return None;
         };
 
@@ -53,7 +51,7 @@ fn left_right(data: &str) -> Option<(Vec<u64>, Vec<u64>)> {
     Some((left, right))
 }
 
-fn part1(data: &str) -> u64 {
+fn part1(data: &Input) -> u64 {
     let Some((mut left, mut right)) = left_right(data) else {
         return 0;
     };
@@ -62,34 +60,34 @@ fn part1(data: &str) -> u64 {
     right.sort();
 
     let mut diff_sum = 0;
-    for (l, r) in left.into_iter().zip(right.into_iter()) {
+    for (l, r) in left.into_iter().zip(right) {
         diff_sum += l.max(r) - l.min(r);
     }
 
     diff_sum
 }
 
-fn part2(data: &str) -> u64 {
+fn part2(data: &Input) -> u64 {
     let Some((left, right)) = left_right(data) else {
         return 0;
     };
 
-    let mut similarity = 0;
-    let right_freq = util::frequency(right.iter());
-
-    for num in left.iter() {
-        similarity += *num * right_freq.get(num).unwrap_or(&0);
-    }
-
-    similarity
+    let right_freq = right.into_iter().collect::<Counter<_>>();
+    left.iter().map(|num| num * right_freq[num]).sum()
 }
 
 #[test]
 fn test1() {
-    assert_eq!(part1(include_str!("../data/day1.1.txt")), 2176849);
+    assert_eq!(
+        part1(&Input::new(include_str!("../data/day1.1.txt"))),
+        2176849
+    );
 }
 
 #[test]
 fn test2() {
-    assert_eq!(part2(include_str!("../data/day1.1.txt")), 23384288);
+    assert_eq!(
+        part2(&Input::new(include_str!("../data/day1.1.txt"))),
+        23384288
+    );
 }
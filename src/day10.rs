@@ -1,18 +1,24 @@
-use std::collections::BTreeSet;
-use crate::util;
+use crate::util::{
+    self,
+    flood::flood_fill_into,
+    search::{self, Search},
+    Connectivity, FastSet, Input,
+};
+use fnv::FnvBuildHasher;
+use std::hash::BuildHasher;
 
 pub fn run(example: bool) {
-    let data = if example {
+    let data = Input::new(if example {
         include_str!("../data/example/day10.1.txt")
     } else {
         include_str!("../data/day10.1.txt")
-    };
+    });
 
     tracing::info!("day 10 part 1{}", if example { " example" } else { "" });
     tracing::info!(
         "day 10 part 1{} result: {}",
         if example { " example" } else { "" },
-        part1(data),
+        part1(&data),
     );
 
     if example {
@@ -23,14 +29,12 @@ pub fn run(example: bool) {
     tracing::info!(
         "day 10 part 2{} result: {}",
         if example { " example" } else { "" },
-        part2(data), // same data
+        part2(&data), // same data
     );
 }
 
-fn parse(data: &str) -> Vec<Vec<u32>> {
-    data.lines()
-        .map(|line| line.chars().map(|c| c.to_digit(10).unwrap_or(1)).collect())
-        .collect()
+fn parse(data: &Input) -> Vec<Vec<u32>> {
+    data.grid_map(|c| c.to_digit(10).unwrap_or(1))
 }
 
 fn trailheads(map: &[Vec<u32>]) -> impl Iterator<Item = (usize, usize)> + '_ {
@@ -41,65 +45,157 @@ fn trailheads(map: &[Vec<u32>]) -> impl Iterator<Item = (usize, usize)> + '_ {
     })
 }
 
-fn accessible_peaks(
+fn trails<H: BuildHasher + Default>(
     map: &[Vec<u32>],
-    current_height: u32,
-    current_row: usize,
-    current_col: usize,
-    paths: &mut usize,
-    peaks: &mut BTreeSet<(usize, usize)>,
-) {
-    tracing::trace!(
-        "at height {}, check {},{} for height {}",
-        current_height,
-        current_row,
-        current_col,
-        current_height + 1
+    row: usize,
+    col: usize,
+) -> Search<(usize, usize), usize, H> {
+    search::bfs_with_hasher((row, col), |&(row, col)| {
+        util::surrounding_cardinal(map, row, col)
+            .filter(move |(next_height, _, _, _)| **next_height == map[row][col] + 1)
+            .map(|(_, next_row, next_col, _)| (next_row, next_col))
+    })
+}
+
+fn peaks<'a, H>(
+    map: &'a [Vec<u32>],
+    trails: &'a Search<(usize, usize), usize, H>,
+) -> impl Iterator<Item = &'a (usize, usize)> + 'a {
+    trails.cost.keys().filter(|&&(row, col)| map[row][col] == 9)
+}
+
+/// peaks reachable from `trailhead`, `S` being what the flood fill collects into
+fn score<S>(map: &[Vec<u32>], trailhead: (usize, usize)) -> usize
+where
+    S: Default + Extend<(usize, usize)> + IntoIterator<Item = (usize, usize)>,
+{
+    let reachable = flood_fill_into::<_, S>(
+        map,
+        trailhead,
+        Connectivity::Four,
+        Some(9),
+        |height, next_height| *next_height == height + 1,
     );
-    for (next_height, next_row, next_col, _) in
-        util::surrounding_cardinal(map, current_row, current_col)
-            .filter(|(next_height, _, _, _)| **next_height == current_height + 1)
-    {
-        if *next_height == 9 {
-            tracing::trace!("found peak at {},{}", next_row, next_col);
-            *paths += 1;
-            peaks.insert((next_row, next_col));
-        } else {
-            accessible_peaks(map, current_height + 1, next_row, next_col, paths, peaks);
-        }
-    }
+    reachable
+        .reached
+        .into_iter()
+        .filter(|&(row, col)| map[row][col] == 9)
+        .count()
 }
 
-fn part1(data: &str) -> usize {
+fn part1(data: &Input) -> usize {
     let map = parse(data);
-    let mut total_score = 0;
-    for (trailhead_row, trailhead_col) in trailheads(&map) {
-        let mut peaks = BTreeSet::new();
-        let mut paths = 0;
-        accessible_peaks(&map, 0, trailhead_row, trailhead_col, &mut paths, &mut peaks);
-        total_score += peaks.len();
-    }
-    total_score
+    trailheads(&map)
+        .map(|trailhead| score::<FastSet<_>>(&map, trailhead))
+        .sum()
+}
+
+/// number of trails from `trailhead` to any peak, with the search hashed by `H`
+fn rating<H: BuildHasher + Default>(map: &[Vec<u32>], (row, col): (usize, usize)) -> usize {
+    let trails = trails::<H>(map, row, col);
+    peaks(map, &trails)
+        .map(|peak| {
+            tracing::trace!("found peak at {:?}", peak);
+            trails.count_paths_to(peak)
+        })
+        .sum()
 }
 
-fn part2(data: &str) -> usize {
+fn part2(data: &Input) -> usize {
     let map = parse(data);
-    let mut total_rating = 0;
-    for (trailhead_row, trailhead_col) in trailheads(&map) {
-        let mut peaks = BTreeSet::new();
-        let mut paths = 0;
-        accessible_peaks(&map, 0, trailhead_row, trailhead_col, &mut paths, &mut peaks);
-        total_rating += paths;
-    }
-    total_rating
+    trailheads(&map)
+        .map(|trailhead| rating::<FnvBuildHasher>(&map, trailhead))
+        .sum()
 }
 
 #[test]
 fn test1() {
-    assert_eq!(part1(include_str!("../data/day10.1.txt")), 552);
+    assert_eq!(part1(&Input::new(include_str!("../data/day10.1.txt"))), 552);
 }
 
 #[test]
 fn test2() {
-    assert_eq!(part2(include_str!("../data/day10.1.txt")), 1225);
+    assert_eq!(
+        part2(&Input::new(include_str!("../data/day10.1.txt"))),
+        1225
+    );
+}
+
+/// slopes going up to the bottom right, with some noise so not every trail gets through
+#[cfg(test)]
+fn generated_map(size: usize) -> Vec<Vec<u32>> {
+    let mut seed = 0x2024u64;
+    (0..size)
+        .map(|row| {
+            (0..size)
+                .map(|col| {
+                    seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1);
+                    if (seed >> 33).is_multiple_of(8) {
+                        (seed >> 40) as u32 % 10
+                    } else {
+                        (row + col) as u32 % 10
+                    }
+                })
+                .collect()
+        })
+        .collect()
+}
+
+#[test]
+#[ignore = "benchmark, run with cargo test --release bench_flood -- --ignored --nocapture"]
+fn bench_flood() {
+    use std::{
+        collections::{hash_map::RandomState, BTreeSet, HashSet},
+        time::Instant,
+    };
+
+    let map = generated_map(300);
+    let starts = trailheads(&map).collect::<Vec<_>>();
+
+    let time = |name: &str, score: &dyn Fn((usize, usize)) -> usize| {
+        let now = Instant::now();
+        let total = starts.iter().map(|&start| score(start)).sum::<usize>();
+        println!(
+            "{}: score {} from {} trailheads in {:?}",
+            name,
+            total,
+            starts.len(),
+            now.elapsed()
+        );
+        total
+    };
+
+    let btree = time("btreeset", &|start| score::<BTreeSet<_>>(&map, start));
+    let sip = time("siphash", &|start| {
+        score::<HashSet<_, RandomState>>(&map, start)
+    });
+    let fnv = time("fnv", &|start| score::<FastSet<_>>(&map, start));
+    assert_eq!(btree, sip);
+    assert_eq!(btree, fnv);
+}
+
+#[test]
+#[ignore = "benchmark, run with cargo test --release bench_search -- --ignored --nocapture"]
+fn bench_search() {
+    use std::{collections::hash_map::RandomState, time::Instant};
+
+    let map = generated_map(300);
+    let starts = trailheads(&map).collect::<Vec<_>>();
+
+    let time = |name: &str, rating: &dyn Fn((usize, usize)) -> usize| {
+        let now = Instant::now();
+        let total = starts.iter().map(|&start| rating(start)).sum::<usize>();
+        println!(
+            "{}: rating {} from {} trailheads in {:?}",
+            name,
+            total,
+            starts.len(),
+            now.elapsed()
+        );
+        total
+    };
+
+    let sip = time("siphash", &|start| rating::<RandomState>(&map, start));
+    let fnv = time("fnv", &|start| rating::<FnvBuildHasher>(&map, start));
+    assert_eq!(sip, fnv);
 }
@@ -1,18 +1,22 @@
-use crate::util;
-use std::collections::BTreeMap;
+use crate::util::{
+    digits,
+    memo::{FastMemo, Memo},
+    Input,
+};
+use std::hash::BuildHasher;
 
 pub fn run(example: bool) {
-    let data = if example {
+    let data = Input::new(if example {
         include_str!("../data/example/day11.1.txt")
     } else {
         include_str!("../data/day11.1.txt")
-    };
+    });
 
     tracing::info!("day 11 part 1{}", if example { " example" } else { "" });
     tracing::info!(
         "day 11 part 1{} result: {}",
         if example { " example" } else { "" },
-        part1(data),
+        part1(&data),
     );
 
     if example {
@@ -23,75 +27,97 @@ pub fn run(example: bool) {
     tracing::info!(
         "day 11 part 2{} result: {}",
         if example { " example" } else { "" },
-        part2(data), // same data
+        part2(&data), // same data
     );
 }
 
-fn split(num: u64) -> (u64, u64) {
-    let left_ones = 10u64.pow(util::num_digits(num) / 2);
-    let left = num / left_ones;
-    let right = num - (left * left_ones);
-    (left, right)
-}
-
-fn blink(times: usize, value: u64) -> u64 {
-    fn inner_blink(
-        times: usize,
-        value: u64,
-        depth: usize,
-        cache: &mut BTreeMap<(u64, usize), u64>,
-    ) -> u64 {
-        //tracing::trace!("{:indent$}{}", "", value, indent = depth);
-        if depth > times {
-            return 1;
-        }
-        if let Some(stones) = cache.get(&(value, depth)) {
-            return *stones;
-        }
-
-        let stones = if value == 0 {
-            inner_blink(times, 1, depth + 1, cache)
-        } else if util::num_digits(value) % 2 != 0 {
-            inner_blink(times, value * 2024, depth + 1, cache)
+fn blink<H: BuildHasher>(memo: &mut Memo<(u64, usize), u64, H>, times: usize, value: u64) -> u64 {
+    memo.get_or_compute((value, times), |blink, &(value, times)| {
+        if times == 0 {
+            1
+        } else if value == 0 {
+            blink((1, times - 1))
+        } else if !digits::count(value).is_multiple_of(2) {
+            blink((value * 2024, times - 1))
         } else {
-            let (left, right) = split(value);
-            inner_blink(times, left, depth + 1, cache) + inner_blink(times, right, depth + 1, cache)
-        };
-        cache.insert((value, depth), stones);
-        stones
-    }
-    let mut cache = BTreeMap::new();
-    inner_blink(times, value, 1, &mut cache)
+            let (left, right) = digits::split(value, digits::count(value) / 2);
+            blink((left, times - 1)) + blink((right, times - 1))
+        }
+    })
 }
 
-fn part1(data: &str) -> u64 {
+fn part1(data: &Input) -> u64 {
+    let mut memo = FastMemo::default();
     let mut total = 0;
     for root in data
+        .text()
         .split_whitespace()
         .map(|word| word.parse::<u64>().unwrap())
     {
-        total += blink(25, root);
+        total += blink(&mut memo, 25, root);
     }
     total
 }
 
-fn part2(data: &str) -> u64 {
+fn part2(data: &Input) -> u64 {
+    let mut memo = FastMemo::default();
     let mut total = 0;
     for root in data
+        .text()
         .split_whitespace()
         .map(|word| word.parse::<u64>().unwrap())
     {
-        total += blink(75, root);
+        total += blink(&mut memo, 75, root);
     }
+    tracing::debug!("{} cached stones", memo.len());
     total
 }
 
 #[test]
 fn test1() {
-    assert_eq!(part1(include_str!("../data/day11.1.txt")), 203457);
+    assert_eq!(
+        part1(&Input::new(include_str!("../data/day11.1.txt"))),
+        203457
+    );
 }
 
 #[test]
 fn test2() {
-    assert_eq!(part2(include_str!("../data/day11.1.txt")), 241394363462435);
+    assert_eq!(
+        part2(&Input::new(include_str!("../data/day11.1.txt"))),
+        241394363462435
+    );
+}
+
+#[test]
+#[ignore = "benchmark, run with cargo test --release bench_memo -- --ignored --nocapture"]
+fn bench_memo() {
+    use std::{collections::hash_map::RandomState, time::Instant};
+
+    let stones = [0, 1, 10, 99, 999, 125, 17, 2024, 77, 8675309];
+    let time = |name: &str, blinks: &mut dyn FnMut() -> u64| {
+        let now = Instant::now();
+        let mut total = 0;
+        for _ in 0..20 {
+            total = blinks();
+        }
+        println!("{}: {} stones, 20 runs in {:?}", name, total, now.elapsed());
+        total
+    };
+
+    let sip = time("siphash", &mut || {
+        let mut memo = Memo::<_, _, RandomState>::default();
+        stones
+            .iter()
+            .map(|&stone| blink(&mut memo, 75, stone))
+            .sum()
+    });
+    let fnv = time("fnv", &mut || {
+        let mut memo = FastMemo::default();
+        stones
+            .iter()
+            .map(|&stone| blink(&mut memo, 75, stone))
+            .sum()
+    });
+    assert_eq!(sip, fnv);
 }
@@ -1,15 +1,17 @@
+use crate::util::Input;
+
 pub fn run(example: bool) {
-    let data = if example {
+    let data = Input::new(if example {
         include_str!("../data/example/day2.1.txt")
     } else {
         include_str!("../data/day2.1.txt")
-    };
+    });
 
     tracing::info!("day 2 part 1{}", if example { " example" } else { "" });
     tracing::info!(
         "day 2 part 1{} result: {}",
         if example { " example" } else { "" },
-        part1(data),
+        part1(&data),
     );
 
     if example {
@@ -20,32 +22,21 @@ pub fn run(example: bool) {
     tracing::info!(
         "day 2 part 2{} result: {}",
         if example { " example" } else { "" },
-        part2(data), // same data
+        part2(&data), // same data
     );
 }
 
-fn lines_numbers(data: &str) -> Option<Vec<Vec<u64>>> {
-    let mut lines = Vec::new();
-
-    for (line_num, line) in data.lines().enumerate() {
-        let mut words = Vec::new();
-
-        for word in line.split_whitespace() {
-            let Ok(num) = word.parse() else {
-                tracing::error!("not a number on line {}: {:?}", line_num, word);
-                return None;
-            };
-
-            words.push(num);
+fn lines_numbers(data: &Input) -> Option<Vec<Vec<u64>>> {
+    match data.numbers() {
+        Ok(lines) => Some(lines),
+        Err(err) => {
+            tracing::error!("{}", err);
+            None
         }
-
-        lines.push(words);
     }
-
-    Some(lines)
 }
 
-fn part1(data: &str) -> u64 {
+fn part1(data: &Input) -> u64 {
     let Some(reports) = lines_numbers(data) else {
         return 0;
     };
@@ -106,7 +97,7 @@ fn is_safe(report: &[u64], skip: Option<usize>) -> bool {
     true
 }
 
-fn part2(data: &str) -> u64 {
+fn part2(data: &Input) -> u64 {
     let Some(reports) = lines_numbers(data) else {
         return 0;
     };
@@ -130,10 +121,10 @@ fn part2(data: &str) -> u64 {
 
 #[test]
 fn test1() {
-    assert_eq!(part1(include_str!("../data/day2.1.txt")), 246);
+    assert_eq!(part1(&Input::new(include_str!("../data/day2.1.txt"))), 246);
 }
 
 #[test]
 fn test2() {
-    assert_eq!(part2(include_str!("../data/day2.1.txt")), 318);
+    assert_eq!(part2(&Input::new(include_str!("../data/day2.1.txt"))), 318);
 }
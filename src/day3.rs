@@ -1,34 +1,35 @@
+use crate::util::Input;
 use logos::Logos;
 
 pub fn run(example: bool) {
-    let data1 = if example {
+    let data1 = Input::new(if example {
         include_str!("../data/example/day3.1.txt")
     } else {
         include_str!("../data/day3.1.txt")
-    };
+    });
 
     tracing::info!("day 3 part 1{}", if example { " example" } else { "" });
     tracing::info!(
         "day 3 part 1{} result: {}",
         if example { " example" } else { "" },
-        part1(data1),
+        part1(&data1),
     );
 
     if example {
         tracing::warn!("used example data");
     }
 
-    let data2 = if example {
+    let data2 = Input::new(if example {
         include_str!("../data/example/day3.2.txt")
     } else {
         include_str!("../data/day3.1.txt")
-    };
+    });
 
     tracing::info!("day 3 part 2{}", if example { " example" } else { "" });
     tracing::info!(
         "day 3 part 2{} result: {}",
         if example { " example" } else { "" },
-        part2(data2),
+        part2(&data2),
     );
 }
 
@@ -189,20 +190,26 @@ fn do_the_thing(data: &str, toggle_stuff: bool) -> u64 {
     sum
 }
 
-fn part1(data: &str) -> u64 {
-    do_the_thing(data, false)
+fn part1(data: &Input) -> u64 {
+    do_the_thing(data.text(), false)
 }
 
-fn part2(data: &str) -> u64 {
-    do_the_thing(data, true)
+fn part2(data: &Input) -> u64 {
+    do_the_thing(data.text(), true)
 }
 
 #[test]
 fn test1() {
-    assert_eq!(part1(include_str!("../data/day3.1.txt")), 182780583);
+    assert_eq!(
+        part1(&Input::new(include_str!("../data/day3.1.txt"))),
+        182780583
+    );
 }
 
 #[test]
 fn test2() {
-    assert_eq!(part2(include_str!("../data/day3.1.txt")), 90772405);
+    assert_eq!(
+        part2(&Input::new(include_str!("../data/day3.1.txt"))),
+        90772405
+    );
 }
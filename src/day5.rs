@@ -1,20 +1,20 @@
-use std::{
-    collections::{HashMap, HashSet},
-    str::FromStr,
+use crate::util::{
+    order::{Cycle, Order},
+    Input,
 };
 
 pub fn run(example: bool) {
-    let data = if example {
+    let data = Input::new(if example {
         include_str!("../data/example/day5.1.txt")
     } else {
         include_str!("../data/day5.1.txt")
-    };
+    });
 
     tracing::info!("day 5 part 1{}", if example { " example" } else { "" });
     tracing::info!(
         "day 5 part 1{} result: {}",
         if example { " example" } else { "" },
-        part1(data),
+        part1(&data),
     );
 
     if example {
@@ -25,7 +25,7 @@ pub fn run(example: bool) {
     tracing::info!(
         "day 5 part 2{} result: {}",
         if example { " example" } else { "" },
-        part2(data), // same data
+        part2(&data), // same data
     );
 }
 
@@ -38,87 +38,40 @@ struct Rule {
 #[derive(Debug)]
 struct OrderRules {
     updates: Vec<Vec<usize>>,
-
-    /// things that can come after a usize
-    prev_nexts: HashMap<usize, HashSet<usize>>,
-
-    /// things that can come before a usize
-    next_prevs: HashMap<usize, HashSet<usize>>,
+    order: Order<usize>,
 }
 
-impl FromStr for OrderRules {
-    type Err = String;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut prev_nexts = HashMap::<usize, HashSet<usize>>::new();
-        let mut next_prevs = HashMap::<usize, HashSet<usize>>::new();
+impl TryFrom<&Input> for OrderRules {
+    type Error = String;
 
-        let mut split = s.split("\n\n");
+    fn try_from(input: &Input) -> Result<Self, Self::Error> {
+        let mut split = input.paragraphs();
         let Some(rules_str) = split.next() else {
             return Err(String::from("no rules"));
         };
         let Some(updates_str) = split.next() else {
-            return Err(String::from("no rules"));
+            return Err(String::from("no updates"));
         };
 
         let mut rules = Vec::new();
         for rule_line in rules_str.lines() {
             let mut split = rule_line.split("|");
             let Some(before_str) = split.next() else {
-                return Err(String::from("no before in rule"));
+                return Err(format!("no before in rule on line {}", rule_line.number));
             };
             let Some(after_str) = split.next() else {
-                return Err(String::from("no after in rule"));
+                return Err(format!("no after in rule on line {}", rule_line.number));
             };
             let Ok(before) = before_str.parse() else {
-                return Err(String::from("before not a number"));
+                return Err(format!("before not a number on line {}", rule_line.number));
             };
             let Ok(after) = after_str.parse() else {
-                return Err(String::from("after not a number"));
+                return Err(format!("after not a number on line {}", rule_line.number));
             };
             rules.push(Rule { before, after });
         }
 
-        for rule in rules.iter() {
-            prev_nexts
-                .entry(rule.before)
-                .or_default()
-                .insert(rule.after);
-            next_prevs
-                .entry(rule.after)
-                .or_default()
-                .insert(rule.before);
-        }
-
-        //let mut real_prev_nexts = prev_nexts.clone();
-        //for (prev, nexts) in prev_nexts.iter() {
-        //    let mut graphed = HashSet::new();
-        //    let mut queue = nexts.clone();
-        //    tracing::trace!("prev={}", prev);
-
-        //    while !queue.is_empty() {
-        //        tracing::trace!("queue={:?}", queue);
-        //        for next in queue.clone() {
-        //            tracing::trace!("remove {}", next);
-        //            queue.remove(&next);
-        //            if graphed.contains(&next) {
-        //                continue;
-        //            }
-        //            graphed.insert(next);
-        //            if let Some(more_nexts) = prev_nexts.get(&next) {
-        //                tracing::trace!("add {:?}", more_nexts);
-        //                queue.extend(more_nexts);
-        //            }
-        //            real_prev_nexts.entry(*prev).or_default().insert(next);
-        //        }
-        //    }
-        //}
-
-        for (&next, prevs) in next_prevs.iter() {
-            for &prev in prevs {
-                prev_nexts.entry(prev).or_default().insert(next);
-            }
-        }
+        let order = rules.iter().map(|rule| (rule.before, rule.after)).collect();
 
         let mut updates = Vec::new();
         for update_line in updates_str.lines() {
@@ -127,43 +80,18 @@ impl FromStr for OrderRules {
                     .split(",")
                     .map(|page| page.parse())
                     .collect::<Result<Vec<_>, _>>()
-                    .map_err(|_| String::from("page not a number"))?,
+                    .map_err(|_| format!("page not a number on line {}", update_line.number))?,
             );
         }
 
-        Ok(OrderRules {
-            //prev_nexts: real_prev_nexts,
-            prev_nexts,
-            next_prevs,
-            updates,
-        })
+        Ok(OrderRules { order, updates })
     }
 }
 
 impl OrderRules {
-    fn is_allowed(&self, prev: &usize, next: &usize) -> bool {
-        self.prev_nexts.get(prev).map(|after| after.contains(next)) == Some(true)
-            || self
-                .next_prevs
-                .get(next)
-                .map(|before| before.contains(prev))
-                == Some(true)
-    }
-
     fn is_in_order(&self, update: &[usize]) -> bool {
         tracing::debug!("checking update {:?}", update);
-        let mut prev = update.first().unwrap();
-        for next in update.iter().skip(1) {
-            if self.is_allowed(prev, next) {
-                tracing::trace!("{} -> {} ok", prev, next);
-            } else {
-                tracing::debug!("{} -> {} not allowed", prev, next);
-                return false;
-            }
-            prev = next;
-        }
-        tracing::debug!("update seems ok");
-        true
+        self.order.is_in_order(update)
     }
 
     fn updates_in_order(&self) -> Vec<&Vec<usize>> {
@@ -182,8 +110,8 @@ impl OrderRules {
     }
 }
 
-fn part1(data: &str) -> usize {
-    let Ok(rules) = data.parse::<OrderRules>() else {
+fn part1(data: &Input) -> usize {
+    let Ok(rules) = OrderRules::try_from(data) else {
         tracing::error!("bad data");
         return 0;
     };
@@ -195,31 +123,8 @@ fn part1(data: &str) -> usize {
         .sum()
 }
 
-fn extract_allowed_subset(rules: &OrderRules, mut update: Vec<usize>) -> (Vec<usize>, Vec<usize>) {
-    let mut removed = Vec::new();
-
-    tracing::debug!("extracting from {:?}", update);
-
-    let mut prev = update[0];
-    let mut ni = 1;
-    while ni < update.len() {
-        let next = update[ni];
-        if !rules.is_allowed(&prev, &next) {
-            tracing::trace!("{} -> {} not allowed, removing {}", prev, next, next);
-            update.remove(ni);
-            removed.push(next);
-        } else {
-            prev = next;
-            ni += 1;
-        }
-    }
-
-    assert!(rules.is_in_order(update.as_slice()), "fundamental flaw");
-    (update, removed)
-}
-
-fn part2(data: &str) -> usize {
-    let Ok(rules) = data.parse::<OrderRules>() else {
+fn part2(data: &Input) -> usize {
+    let Ok(rules) = OrderRules::try_from(data) else {
         tracing::error!("bad data");
         return 0;
     };
@@ -227,30 +132,20 @@ fn part2(data: &str) -> usize {
 
     let mut center_sum = 0;
 
-    for update in rules.cloned_updates_out_of_order() {
+    for mut update in rules.cloned_updates_out_of_order() {
         tracing::debug!("fixing update {:?}", update);
 
-        let (mut update, removed) = extract_allowed_subset(&rules, update);
-
-        tracing::debug!("left over: {:?}", update);
-        tracing::debug!("removed: {:?}", removed);
-
-        for item in removed {
-            let mut i = update.len() - 1;
-            while i > 0 {
-                if rules.is_allowed(&update[i - 1], &item) {
-                    tracing::trace!("{} -> {} ok", update[i - 1], item);
-                    break;
-                }
-                tracing::trace!("{} -> {} not allowed", update[i - 1], item);
-                i -= 1;
+        // the whole rule set goes round in circles, so only the rules between these pages
+        let comparator = match rules.order.subset(&update).comparator(&update) {
+            Ok(comparator) => comparator,
+            Err(Cycle(cycle)) => {
+                tracing::error!("rules for update have a cycle: {:?}", cycle);
+                return 0;
             }
-            update.insert(i, item);
-            tracing::trace!("inserted at {}: {:?}", i, update);
-        }
+        };
+        update.sort_by(comparator);
 
         tracing::debug!("fixed: {:?}", update);
-        assert!(rules.is_in_order(update.as_slice()), "nooooooo 😭😭😭😭😭😿😿😿😿");
         center_sum += update[update.len() / 2];
     }
 
@@ -259,10 +154,10 @@ fn part2(data: &str) -> usize {
 
 #[test]
 fn test1() {
-    assert_eq!(part1(include_str!("../data/day5.1.txt")), 4462);
+    assert_eq!(part1(&Input::new(include_str!("../data/day5.1.txt"))), 4462);
 }
 
 #[test]
 fn test2() {
-    assert_eq!(part2(include_str!("../data/day5.1.txt")), 6767);
+    assert_eq!(part2(&Input::new(include_str!("../data/day5.1.txt"))), 6767);
 }
@@ -1,18 +1,18 @@
-use crate::util::Direction;
-use std::{collections::{BTreeSet, BTreeMap}, fmt::Display};
+use crate::util::{line, BitGrid, DenseMap, Direction, Input};
+use std::fmt::Display;
 
 pub fn run(example: bool) {
-    let data = if example {
+    let data = Input::new(if example {
         include_str!("../data/example/day6.1.txt")
     } else {
         include_str!("../data/day6.1.txt")
-    };
+    });
 
     tracing::info!("day 6 part 1{}", if example { " example" } else { "" });
     tracing::info!(
         "day 6 part 1{} result: {}",
         if example { " example" } else { "" },
-        part1(data),
+        part1(&data),
     );
 
     if example {
@@ -23,7 +23,7 @@ pub fn run(example: bool) {
     tracing::info!(
         "day 6 part 2{} result: {}",
         if example { " example" } else { "" },
-        part2(data), // same data
+        part2(&data), // same data
     );
 }
 
@@ -60,169 +60,240 @@ impl Display for Tile {
     }
 }
 
-fn parse(data: &str) -> Vec<Vec<Tile>> {
-    data.lines()
-        .map(|line| {
-            line.chars()
-                .map(|c| match c {
-                    '#' => Tile::Obstacle(false),
-                    '^' => Tile::Guard(Direction::N),
-                    'v' => Tile::Guard(Direction::S),
-                    '>' => Tile::Guard(Direction::E),
-                    '<' => Tile::Guard(Direction::W),
-                    '.' => Tile::Empty,
-                    _ => panic!("bad tile: {:?}", c),
-                })
-                .collect()
-        })
-        .collect()
+fn parse(data: &Input) -> Vec<Vec<Tile>> {
+    data.grid_map(|c| match c {
+        '#' => Tile::Obstacle(false),
+        '^' => Tile::Guard(Direction::N),
+        'v' => Tile::Guard(Direction::S),
+        '>' => Tile::Guard(Direction::E),
+        '<' => Tile::Guard(Direction::W),
+        '.' => Tile::Empty,
+        _ => panic!("bad tile: {:?}", c),
+    })
 }
 
-fn visited(mut plane: Vec<Vec<Tile>>) -> (BTreeSet<(usize, usize, Direction)>, bool) {
-    //let mut vis: Vec<Vec<String>> = plane
-    //    .iter()
-    //    .map(|row| row.iter().map(|tile| format!("{}", tile)).collect())
-    //    .collect();
+type Guard = (usize, usize, Direction);
 
-    let mut guard = None;
+fn guard(plane: &[Vec<Tile>]) -> Option<Guard> {
     for (rowi, row) in plane.iter().enumerate() {
         for (coli, tile) in row.iter().enumerate() {
-            if matches!(tile, Tile::Guard(_)) {
-                guard = Some((rowi, coli));
+            if let Some(dir) = tile.dir() {
+                return Some((rowi, coli, dir));
             }
         }
     }
+    None
+}
 
-    let Some((mut guard_row, mut guard_col)) = guard else {
-        tracing::error!("no guard");
-        return (BTreeSet::default(), false);
-    };
-    let mut guard_dir = plane[guard_row][guard_col].dir().unwrap();
-
-    let mut visited = BTreeSet::default();
-
-    visited.insert((guard_row, guard_col, guard_dir));
-    //vis[guard_row][guard_col] = String::from("X");
-
-    let mut cycle = false;
-    let mut hit_obstacles = BTreeMap::<(usize, usize), usize>::default();
+/// where the guard is after one step, None once they leave
+fn step(plane: &[Vec<Tile>], &(guard_row, guard_col, guard_dir): &Guard) -> Option<Guard> {
+    let (next_row, next_col) = guard_dir.apply_index(plane, guard_row, guard_col)?;
 
-    while let Some((next_row, next_col)) =
-        guard_dir.apply_index(plane.as_slice(), guard_row, guard_col)
-    {
-        if hit_obstacles.get(&(next_row, next_col)) == Some(&2) {
-            cycle = true;
-            tracing::debug!("cycle");
-            break;
+    match plane[next_row][next_col] {
+        Tile::Obstacle(_) => {
+            tracing::trace!(
+                "obstacle {:?} at {},{}: {:?} next",
+                guard_dir,
+                next_row,
+                next_col,
+                guard_dir.right_90()
+            );
+            Some((guard_row, guard_col, guard_dir.right_90()))
         }
 
-        tracing::trace!(
-            "at {},{} looking {:?} at {},{}",
-            guard_row,
-            guard_col,
-            guard_dir,
-            next_row,
-            next_col
-        );
-        //for row in vis.iter() {
-        //    tracing::trace!("{}", row.join(""));
-        //}
-
-        match guard_dir
-            .apply(plane.as_slice(), guard_row, guard_col)
-            .unwrap()
-        {
-            Tile::Obstacle(_) => {
-                tracing::trace!(
-                    "obstacle {:?} at {},{}: {:?} next",
-                    guard_dir,
-                    next_row,
-                    next_col,
-                    guard_dir.right_90()
-                );
-                guard_dir = guard_dir.right_90();
-                *hit_obstacles.entry((next_row, next_col)).or_default() += 1;
-            }
-
-            Tile::Empty => {
-                tracing::trace!("empty {:?} at {},{}", guard_dir, next_row, next_col);
-                plane[guard_row][guard_col] = Tile::Empty;
-                plane[next_row][next_col] = Tile::Guard(guard_dir);
-                visited.insert((next_row, next_col, guard_dir));
-                guard_row = next_row;
-                guard_col = next_col;
-                //vis[guard_row][guard_col] = String::from("X");
-            }
-
-            _ => unreachable!("guards plural?"),
+        Tile::Empty | Tile::Guard(_) => {
+            tracing::trace!("empty {:?} at {},{}", guard_dir, next_row, next_col);
+            Some((next_row, next_col, guard_dir))
         }
     }
+}
 
-    if cycle {
-        //for row in vis.iter() {
-        //    tracing::debug!("{}", row.join(""));
-        //}
+fn visited(plane: &[Vec<Tile>]) -> BitGrid {
+    let mut visited = BitGrid::for_plane(plane, 1);
+    let Some(mut guard) = guard(plane) else {
+        tracing::error!("no guard");
+        return visited;
+    };
+
+    visited.insert(guard.0, guard.1);
+    while let Some(next) = step(plane, &guard) {
+        visited.insert(next.0, next.1);
+        guard = next;
     }
 
-    (visited, cycle)
+    visited
 }
 
-fn part1(data: &str) -> usize {
-    let plane = parse(data);
-
-    visited(plane)
-        .0
-        .into_iter()
-        .map(|(row, col, _)| (row, col))
-        .collect::<BTreeSet<_>>()
-        .len()
+/// the guard just before they first step into each cell, except the one they start in
+fn first_steps(plane: &[Vec<Tile>], start: Guard) -> DenseMap<Guard> {
+    let mut first_steps = DenseMap::for_plane(plane, 1);
+    let mut guard = start;
+    while let Some(next) = step(plane, &guard) {
+        if (next.0, next.1) != (start.0, start.1) && !first_steps.contains(next.0, next.1) {
+            first_steps.insert(next.0, next.1, guard);
+        }
+        guard = next;
+    }
+    first_steps
 }
 
-fn part2(data: &str) -> usize {
-    let plane = parse(data);
-    let visited_dirs = visited(plane.clone()).0;
-    tracing::debug!(
-        "visited {} locations with different directions",
-        visited_dirs.len()
-    );
+/// whether the guard ends up walking in circles. they go straight to the next obstacle each
+/// time, so only the spots they turn at get remembered in `seen`, one layer per direction
+fn loops(plane: &[Vec<Tile>], mut guard: Guard, seen: &mut BitGrid) -> bool {
+    seen.clear();
+    loop {
+        let (row_off, col_off) = guard.2.to_offset();
+        let Some((row, col)) = line::cast(plane, (guard.0, guard.1), (row_off, col_off), |tile| {
+            matches!(tile, Tile::Obstacle(_))
+        }) else {
+            return false;
+        };
 
-    let mut guard = None;
-    for (rowi, row) in plane.iter().enumerate() {
-        for (coli, tile) in row.iter().enumerate() {
-            if matches!(tile, Tile::Guard(_)) {
-                guard = Some((rowi, coli));
-            }
+        guard = (
+            row.wrapping_add_signed(-row_off),
+            col.wrapping_add_signed(-col_off),
+            guard.2.right_90(),
+        );
+        if !seen.insert_in(guard.2.index(), guard.0, guard.1) {
+            return true;
         }
     }
-    let Some((guard_row, guard_col)) = guard else {
+}
+
+fn part1(data: &Input) -> usize {
+    let plane = parse(data);
+    visited(&plane).len()
+}
+
+fn part2(data: &Input) -> usize {
+    let mut plane = parse(data);
+    let Some(start) = guard(&plane) else {
         tracing::error!("no guard");
         return 0;
     };
 
-    // brute force go brrrrr
-    let mut cycles = BTreeSet::default();
-    for (visited_row, visited_col, _) in visited_dirs {
-        if (visited_row, visited_col) == (guard_row, guard_col) {
-            continue;
-        }
-        let mut plane = plane.clone();
-        plane[visited_row][visited_col] = Tile::Obstacle(true);
-        let (tiles, cycle) = visited(plane);
-        if cycle {
-            tracing::debug!("cycle?");
-            cycles.insert(tiles.into_iter().collect::<std::collections::BTreeSet<_>>());
+    let first_steps = first_steps(&plane, start);
+    tracing::debug!("visited {} locations", first_steps.len() + 1);
+
+    // nothing changes until the guard walks into the new obstacle, so start from there
+    let mut seen = BitGrid::for_plane(&plane, Direction::ALL.len());
+    let mut cycles = 0;
+    for ((_, row, col), &before) in first_steps.iter() {
+        plane[row][col] = Tile::Obstacle(true);
+        if loops(&plane, before, &mut seen) {
+            tracing::debug!("obstacle at {},{} makes a loop", row, col);
+            cycles += 1;
         }
+        plane[row][col] = Tile::Empty;
     }
 
-    cycles.len()
+    cycles
 }
 
 #[test]
 fn test1() {
-    assert_eq!(part1(include_str!("../data/day6.1.txt")), 5067);
+    assert_eq!(part1(&Input::new(include_str!("../data/day6.1.txt"))), 5067);
 }
 
 #[test]
 fn test2() {
-    assert_eq!(part2(include_str!("../data/day6.1.txt")), 1793);
+    assert_eq!(part2(&Input::new(include_str!("../data/day6.1.txt"))), 1793);
+}
+
+/// random-ish obstacles, guard in the middle facing up
+#[cfg(test)]
+fn generated_plane(size: usize) -> Vec<Vec<Tile>> {
+    let mut seed = 0x2024u64;
+    let mut plane = (0..size)
+        .map(|_| {
+            (0..size)
+                .map(|_| {
+                    seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1);
+                    if (seed >> 33).is_multiple_of(30) {
+                        Tile::Obstacle(false)
+                    } else {
+                        Tile::Empty
+                    }
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    plane[size / 2][size / 2] = Tile::Guard(Direction::N);
+    plane
+}
+
+#[test]
+#[ignore = "benchmark, run with cargo test --release bench_loops -- --ignored --nocapture"]
+fn bench_loops() {
+    use crate::util::FastSet;
+    use std::{collections::BTreeSet, time::Instant};
+
+    fn loops_btree(plane: &[Vec<Tile>], mut guard: Guard) -> bool {
+        let mut seen = BTreeSet::new();
+        while seen.insert(guard) {
+            match step(plane, &guard) {
+                Some(next) => guard = next,
+                None => return false,
+            }
+        }
+        true
+    }
+
+    fn loops_fast_set(plane: &[Vec<Tile>], mut guard: Guard) -> bool {
+        let mut seen = FastSet::default();
+        while seen.insert(guard) {
+            match step(plane, &guard) {
+                Some(next) => guard = next,
+                None => return false,
+            }
+        }
+        true
+    }
+
+    // what `loops` did before it went straight to the next obstacle
+    fn loops_bitgrid(plane: &[Vec<Tile>], mut guard: Guard, seen: &mut BitGrid) -> bool {
+        seen.clear();
+        while seen.insert_in(guard.2.index(), guard.0, guard.1) {
+            match step(plane, &guard) {
+                Some(next) => guard = next,
+                None => return false,
+            }
+        }
+        true
+    }
+
+    let mut plane = generated_plane(130);
+    let start = guard(&plane).unwrap();
+    let first_steps = first_steps(&plane, start);
+    let mut seen = BitGrid::for_plane(&plane, Direction::ALL.len());
+
+    let mut time = |name: &str, loops: &mut dyn FnMut(&[Vec<Tile>], Guard) -> bool| {
+        let now = Instant::now();
+        let mut cycles = 0;
+        for ((_, row, col), &before) in first_steps.iter() {
+            plane[row][col] = Tile::Obstacle(true);
+            cycles += loops(&plane, before) as usize;
+            plane[row][col] = Tile::Empty;
+        }
+        println!(
+            "{}: {} loops from {} obstacles in {:?}",
+            name,
+            cycles,
+            first_steps.len(),
+            now.elapsed()
+        );
+        cycles
+    };
+
+    let btree = time("btreeset", &mut loops_btree);
+    let fast = time("fastset", &mut loops_fast_set);
+    let stepping = time("bitgrid", &mut |plane, guard| {
+        loops_bitgrid(plane, guard, &mut seen)
+    });
+    let casting = time("bitgrid, casting", &mut |plane, guard| {
+        loops(plane, guard, &mut seen)
+    });
+    assert_eq!(btree, fast);
+    assert_eq!(btree, stepping);
+    assert_eq!(btree, casting);
 }
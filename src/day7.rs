@@ -3,20 +3,20 @@ use std::{
     str::FromStr,
 };
 
-use crate::util;
+use crate::util::{digits, scan, Input};
 
 pub fn run(example: bool) {
-    let data = if example {
+    let data = Input::new(if example {
         include_str!("../data/example/day7.1.txt")
     } else {
         include_str!("../data/day7.1.txt")
-    };
+    });
 
     tracing::info!("day 7 part 1{}", if example { " example" } else { "" });
     tracing::info!(
         "day 7 part 1{} result: {}",
         if example { " example" } else { "" },
-        part1(data),
+        part1(&data),
     );
 
     if example {
@@ -27,7 +27,7 @@ pub fn run(example: bool) {
     tracing::info!(
         "day 7 part 2{} result: {}",
         if example { " example" } else { "" },
-        part2(data), // same data
+        part2(&data), // same data
     );
 }
 
@@ -41,18 +41,15 @@ impl FromStr for Equation {
     type Err = String;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut split = s.split_whitespace();
-
-        let Some(value_str) = split.next() else {
-            return Err(String::from("missing value"));
-        };
-        let value = value_str[..value_str.len() - 1]
-            .parse()
-            .map_err(|_| String::from("value not a number"))?;
+        let column_err = |err: scan::ScanError| format!("column {}: {}", err.column, err.message);
+        let captures = scan::captures("{}: {}", s).map_err(column_err)?;
+        let value = captures.parse(0).map_err(column_err)?;
 
         Ok(Equation {
             value,
-            factors: split
+            factors: captures
+                .get(1)
+                .split_whitespace()
                 .map(|part| part.parse())
                 .collect::<Result<Vec<_>, _>>()
                 .map_err(|_| String::from("part not a number"))?,
@@ -60,15 +57,17 @@ impl FromStr for Equation {
     }
 }
 
-fn parse(data: &str) -> Vec<Equation> {
+fn parse(data: &Input) -> Vec<Equation> {
     data.lines()
-        .filter(|&line| !line.is_empty())
-        .map(Equation::from_str)
+        .map(|line| {
+            line.parse::<Equation>()
+                .map_err(|err| format!("line {}: {}", line.number, err))
+        })
         .collect::<Result<_, _>>()
         .unwrap()
 }
 
-fn part1(data: &str) -> u64 {
+fn part1(data: &Input) -> u64 {
     let equations = parse(data);
     tracing::debug!("{:?}", equations);
 
@@ -187,11 +186,7 @@ fn part1(data: &str) -> u64 {
     sum
 }
 
-fn concat(l: u64, r: u64) -> u64 {
-    l * 10u64.pow(util::num_digits(r)) + r
-}
-
-fn part2(data: &str) -> u64 {
+fn part2(data: &Input) -> u64 {
     let equations = parse(data);
     tracing::debug!("{:?}", equations);
 
@@ -229,10 +224,10 @@ fn part2(data: &str) -> u64 {
             let factor = factors[factor_index];
 
             tracing::trace!(
-                "{v}: {a} + {f} = {}, {a} * {f} = {}, {a} | {f} = {}",
+                "{v}: {a} + {f} = {}, {a} * {f} = {}, {a} | {f} = {:?}",
                 acc + factor,
                 acc * factor,
-                concat(acc, factor),
+                digits::checked_concat(acc, factor),
                 v = value,
                 a = acc,
                 f = factor,
@@ -246,7 +241,7 @@ fn part2(data: &str) -> u64 {
                     tracing::trace!("solution found");
                     solution.push(Op::Mul);
                     true
-                } else if concat(acc, factor) == value {
+                } else if digits::checked_concat(acc, factor) == Some(value) {
                     tracing::trace!("solution found");
                     solution.push(Op::Con);
                     true
@@ -268,13 +263,9 @@ fn part2(data: &str) -> u64 {
                     solution.reverse();
                 }
                 true
-            } else if solve(
-                value,
-                factors,
-                factor_index + 1,
-                concat(acc, factor),
-                solution,
-            ) {
+            } else if digits::checked_concat(acc, factor)
+                .is_some_and(|joined| solve(value, factors, factor_index + 1, joined, solution))
+            {
                 if factor_index != 0 {
                     solution.push(Op::Con);
                 } else {
@@ -298,7 +289,7 @@ fn part2(data: &str) -> u64 {
                 match op {
                     Op::Add => check_value += factor,
                     Op::Mul => check_value *= factor,
-                    Op::Con => check_value = concat(check_value, factor),
+                    Op::Con => check_value = digits::concat(check_value, factor),
                 }
             }
             assert_eq!(check_value, value, "solution invalid");
@@ -332,10 +323,16 @@ fn part2(data: &str) -> u64 {
 
 #[test]
 fn test1() {
-    assert_eq!(part1(include_str!("../data/day7.1.txt")), 945512582195);
+    assert_eq!(
+        part1(&Input::new(include_str!("../data/day7.1.txt"))),
+        945512582195
+    );
 }
 
 #[test]
 fn test2() {
-    assert_eq!(part2(include_str!("../data/day7.1.txt")), 271691107779347);
+    assert_eq!(
+        part2(&Input::new(include_str!("../data/day7.1.txt"))),
+        271691107779347
+    );
 }
@@ -1,19 +1,18 @@
-use crate::util;
+use crate::util::{line, FastMap, FastSet, Input};
 use itertools::Itertools;
-use std::collections::{BTreeMap, BTreeSet};
 
 pub fn run(example: bool) {
-    let data = if example {
+    let data = Input::new(if example {
         include_str!("../data/example/day8.1.txt")
     } else {
         include_str!("../data/day8.1.txt")
-    };
+    });
 
     tracing::info!("day 8 part 1{}", if example { " example" } else { "" });
     tracing::info!(
         "day 8 part 1{} result: {}",
         if example { " example" } else { "" },
-        part1(data),
+        part1(&data),
     );
 
     if example {
@@ -24,7 +23,7 @@ pub fn run(example: bool) {
     tracing::info!(
         "day 8 part 2{} result: {}",
         if example { " example" } else { "" },
-        part2(data), // same data
+        part2(&data), // same data
     );
 }
 
@@ -34,26 +33,20 @@ enum Location {
     Antenna(char),
 }
 
-fn parse(data: &str) -> Vec<Vec<Location>> {
-    data.lines()
-        .map(|line| {
-            line.chars()
-                .map(|c| match c {
-                    '.' => Location::Empty,
-                    _ => Location::Antenna(c),
-                })
-                .collect()
-        })
-        .collect()
+fn parse(data: &Input) -> Vec<Vec<Location>> {
+    data.grid_map(|c| match c {
+        '.' => Location::Empty,
+        _ => Location::Antenna(c),
+    })
 }
 
-fn freq_locations(plane: &[Vec<Location>]) -> BTreeMap<char, BTreeSet<(usize, usize)>> {
-    let mut locations = BTreeMap::<char, BTreeSet<(usize, usize)>>::new();
+fn freq_locations(plane: &[Vec<Location>]) -> FastMap<char, Vec<(usize, usize)>> {
+    let mut locations = FastMap::<char, Vec<(usize, usize)>>::default();
 
     for (row, r) in plane.iter().enumerate() {
         for (col, l) in r.iter().enumerate() {
             if let Location::Antenna(a) = *l {
-                locations.entry(a).or_default().insert((row, col));
+                locations.entry(a).or_default().push((row, col));
             }
         }
     }
@@ -61,68 +54,33 @@ fn freq_locations(plane: &[Vec<Location>]) -> BTreeMap<char, BTreeSet<(usize, us
     locations
 }
 
-fn antinodes(
-    plane: &[Vec<Location>],
-    range: impl Iterator<Item = usize> + Clone,
-) -> BTreeSet<(isize, isize)> {
-    let mut antinodes = BTreeSet::new();
+/// with `resonant` every cell in line with two antennas, otherwise just the ones one more
+/// antenna-gap past either end. any set will do, see `bench_antinodes`
+fn antinodes<S>(plane: &[Vec<Location>], resonant: bool) -> S
+where
+    S: Default + Extend<(usize, usize)>,
+{
+    let mut antinodes = S::default();
 
     let freq_locations = freq_locations(plane);
     for (antenna, locations) in freq_locations.iter() {
         for combo in locations.iter().combinations(2) {
-            let [(a_row, a_col), (b_row, b_col)] = combo[..] else {
+            let [&a, &b] = combo[..] else {
                 tracing::error!("not combo size 2");
                 return antinodes;
             };
-            let (a_row, a_col, b_row, b_col) = (
-                *a_row as isize,
-                *a_col as isize,
-                *b_row as isize,
-                *b_col as isize,
-            );
-
-            let row_diff = a_row - b_row;
-            let col_diff = a_col - b_col;
-
-            tracing::trace!(
-                "{:?} at {},{} ({},{}) {},{}",
-                antenna,
-                a_row,
-                a_col,
-                row_diff,
-                col_diff,
-                b_row,
-                b_col,
-            );
-
-            for factor in range.clone() {
-                let mut had_in_bounds = false;
-
-                let factor = factor as isize;
-                let a_antinode_row = a_row + (row_diff * factor);
-                let a_antinode_col = a_col + (col_diff * factor);
-                if util::in_bounds_2d(plane, a_antinode_row, a_antinode_col) {
-                    tracing::trace!("antinode at {},{}", a_antinode_row, a_antinode_col);
-                    antinodes.insert((a_antinode_row, a_antinode_col));
-                    had_in_bounds = true;
-                } else {
-                    tracing::trace!("antinode not in bounds");
-                }
-
-                let b_antinode_row = b_row - (row_diff * factor);
-                let b_antinode_col = b_col - (col_diff * factor);
-                if util::in_bounds_2d(plane, b_antinode_row, b_antinode_col) {
-                    tracing::trace!("antinode at {},{}", b_antinode_row, b_antinode_col);
-                    antinodes.insert((b_antinode_row, b_antinode_col));
-                    had_in_bounds = true;
-                } else {
-                    tracing::trace!("antinode not in bounds");
-                }
 
-                if !had_in_bounds {
-                    tracing::trace!("no more in-bounds antinodes");
-                    break;
-                }
+            let diff = (a.0 as isize - b.0 as isize, a.1 as isize - b.1 as isize);
+            tracing::trace!("{:?} at {:?} ({:?}) {:?}", antenna, a, diff, b);
+
+            if resonant {
+                let step = line::reduce(diff);
+                antinodes.extend([a]);
+                antinodes.extend(line::ray(plane, a, step));
+                antinodes.extend(line::ray(plane, a, (-step.0, -step.1)));
+            } else {
+                antinodes.extend(line::ray(plane, a, diff).take(1));
+                antinodes.extend(line::ray(plane, b, (-diff.0, -diff.1)).take(1));
             }
         }
     }
@@ -130,20 +88,20 @@ fn antinodes(
     antinodes
 }
 
-fn debug_vis(plane: &[Vec<Location>], antinodes: &BTreeSet<(isize, isize)>) {
+fn debug_vis(plane: &[Vec<Location>], antinodes: &FastSet<(usize, usize)>) {
     for (row, r) in plane.iter().enumerate() {
         let mut line = String::new();
         for (col, l) in r.iter().enumerate() {
             line.push(match l {
                 Location::Empty => {
-                    if antinodes.contains(&(row as isize, col as isize)) {
+                    if antinodes.contains(&(row, col)) {
                         '#'
                     } else {
                         '.'
                     }
                 }
                 Location::Antenna(a) => {
-                    if antinodes.contains(&(row as isize, col as isize)) {
+                    if antinodes.contains(&(row, col)) {
                         '*'
                     } else {
                         *a
@@ -155,26 +113,80 @@ fn debug_vis(plane: &[Vec<Location>], antinodes: &BTreeSet<(isize, isize)>) {
     }
 }
 
-fn part1(data: &str) -> usize {
+fn part1(data: &Input) -> usize {
     let plane = parse(data);
-    let antinodes = antinodes(&plane, 1..=1);
+    let antinodes = antinodes::<FastSet<_>>(&plane, false);
     debug_vis(&plane, &antinodes);
     antinodes.len()
 }
 
-fn part2(data: &str) -> usize {
+fn part2(data: &Input) -> usize {
     let plane = parse(data);
-    let antinodes = antinodes(&plane, 0..);
+    let antinodes = antinodes::<FastSet<_>>(&plane, true);
     debug_vis(&plane, &antinodes);
     antinodes.len()
 }
 
 #[test]
 fn test1() {
-    assert_eq!(part1(include_str!("../data/day8.1.txt")), 396);
+    assert_eq!(part1(&Input::new(include_str!("../data/day8.1.txt"))), 396);
 }
 
 #[test]
 fn test2() {
-    assert_eq!(part2(include_str!("../data/day8.1.txt")), 1200);
+    assert_eq!(part2(&Input::new(include_str!("../data/day8.1.txt"))), 1200);
+}
+
+#[test]
+#[ignore = "benchmark, run with cargo test --release bench_antinodes -- --ignored --nocapture"]
+fn bench_antinodes() {
+    use std::{
+        collections::{hash_map::RandomState, BTreeSet, HashSet},
+        time::Instant,
+    };
+
+    // a few antennas of each frequency scattered about, like the puzzle
+    let mut seed = 0x2024u64;
+    let size = 200;
+    let plane = (0..size)
+        .map(|_| {
+            (0..size)
+                .map(|_| {
+                    seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1);
+                    if (seed >> 33).is_multiple_of(40) {
+                        Location::Antenna((b'a' + (seed >> 40) as u8 % 26) as char)
+                    } else {
+                        Location::Empty
+                    }
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    let time = |name: &str, antinodes: &dyn Fn(bool) -> usize| {
+        let now = Instant::now();
+        let mut counts = (0, 0);
+        for _ in 0..20 {
+            counts = (antinodes(false), antinodes(true));
+        }
+        println!(
+            "{}: {:?} antinodes, 20 runs in {:?}",
+            name,
+            counts,
+            now.elapsed()
+        );
+        counts
+    };
+
+    let btree = time("btreeset", &|resonant| {
+        antinodes::<BTreeSet<_>>(&plane, resonant).len()
+    });
+    let sip = time("siphash", &|resonant| {
+        antinodes::<HashSet<_, RandomState>>(&plane, resonant).len()
+    });
+    let fnv = time("fnv", &|resonant| {
+        antinodes::<FastSet<_>>(&plane, resonant).len()
+    });
+    assert_eq!(btree, sip);
+    assert_eq!(btree, fnv);
 }
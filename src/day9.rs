@@ -1,18 +1,20 @@
+use crate::util::{Input, RangeSet};
 use itertools::Itertools;
+use std::ops::Range;
 use tracing::Level;
 
 pub fn run(example: bool) {
-    let data = if example {
+    let data = Input::new(if example {
         include_str!("../data/example/day9.1.txt")
     } else {
         include_str!("../data/really_evil_input.txt")
-    };
+    });
 
     tracing::info!("day 9 part 1{}", if example { " example" } else { "" });
     tracing::info!(
         "day 9 part 1{} result: {}",
         if example { " example" } else { "" },
-        part1(data),
+        part1(&data),
     );
 
     if example {
@@ -23,7 +25,7 @@ pub fn run(example: bool) {
     tracing::info!(
         "day 9 part 2{} result: {}",
         if example { " example" } else { "" },
-        part2(data), // same data
+        part2(&data), // same data
     );
 }
 
@@ -85,32 +87,33 @@ fn vis_size_space(size_space: &[SizeSpace], level: Level) {
     }
 }
 
-fn blocks(size_space: &[SizeSpace]) -> (Vec<Option<usize>>, usize) {
-    let mut num_blocks = 0;
-    let mut map = Vec::new();
+/// where each file starts out, in disk order
+fn layout(size_space: &[SizeSpace]) -> Vec<(usize, Range<usize>)> {
+    let mut pos = 0;
+    let mut files = Vec::new();
     for SizeSpace { id, size, space } in size_space {
-        num_blocks += *size as usize;
-        map.extend(std::iter::repeat(Some(*id as usize)).take(*size as usize));
-        map.extend(std::iter::repeat(None).take(*space as usize));
+        files.push((*id as usize, pos..pos + *size as usize));
+        pos += (*size + *space) as usize;
     }
 
-    (map, num_blocks)
+    files
 }
 
-fn vis_blocks(map: &[Option<usize>], level: Level) {
+fn vis_files(files: &[(usize, Range<usize>)], level: Level) {
     if tracing::enabled!(Level::TRACE) || tracing::enabled!(Level::DEBUG) {
+        let mut files = files.to_vec();
+        files.sort_by_key(|(_, blocks)| blocks.start);
+
         let mut s = String::new();
-        for space in map {
-            match space {
-                Some(id) => {
-                    if *id >= 10 {
-                        s.push_str(&format!("({})", id))
-                    } else {
-                        s.push_str(&format!("{}", id))
-                    }
-                }
-                None => s.push('.'),
+        let mut pos = 0;
+        for (id, blocks) in files {
+            s.push_str(&".".repeat(blocks.start - pos));
+            if id >= 10 {
+                s.push_str(&format!("({})", id).repeat(blocks.len()));
+            } else {
+                s.push_str(&format!("{}", id).repeat(blocks.len()));
             }
+            pos = blocks.end;
         }
         if level == Level::DEBUG {
             tracing::debug!("{}", s);
@@ -121,133 +124,85 @@ fn vis_blocks(map: &[Option<usize>], level: Level) {
     }
 }
 
-fn find_free_forward(map: &[Option<usize>], from: usize) -> Option<usize> {
-    let mut i = from;
-    while i < map.len() {
-        if map[i].is_none() {
-            return Some(i);
-        }
-        i += 1;
-    }
-    None
-}
-
-fn find_occupied_backward(map: &[Option<usize>], from: usize) -> Option<usize> {
-    let mut i = from;
-    while i > 0 {
-        if map[i].is_some() {
-            return Some(i);
-        }
-        i -= 1;
-    }
-    None
+fn checksum(files: &[(usize, Range<usize>)]) -> usize {
+    files
+        .iter()
+        .map(|(id, blocks)| id * blocks.clone().sum::<usize>())
+        .sum()
 }
 
-fn part1(data: &str) -> usize {
-    let size_space = size_space(data);
-    let (mut map, mut num_blocks) = blocks(&size_space);
-    vis_blocks(&map, Level::DEBUG);
-
-    let mut free_i = find_free_forward(&map, 0).expect("no space left on device");
-    let mut prev_block_i = map.len();
-    num_blocks -= free_i;
-    tracing::trace!(
-        "{} left, skipped {}, {} to {}",
-        num_blocks,
-        free_i,
-        0,
-        free_i
-    );
-
-    while let Some(block_i) = find_occupied_backward(&map, prev_block_i - 1) {
-        map.swap(free_i, block_i);
-        vis_blocks(&map, Level::TRACE);
-
-        let next_free_i = find_free_forward(&map, free_i).expect("no space left on device");
-        let skipped = next_free_i - free_i;
-        num_blocks -= skipped;
-        tracing::trace!(
-            "{} left, skipped {} ({} to {}), swap {:?} and {:?}",
-            num_blocks,
-            skipped,
-            free_i,
-            next_free_i,
-            map[block_i],
-            map[free_i]
-        );
-        if num_blocks == 0 {
-            break;
-        }
-        free_i = next_free_i;
-
-        prev_block_i = block_i;
-    }
-
-    vis_blocks(&map, Level::DEBUG);
-
-    let mut checksum = 0;
-    for (pos, id) in map.iter().enumerate() {
-        if id.is_none() {
-            break;
+fn part1(data: &Input) -> usize {
+    let files = layout(&size_space(data.text()));
+    vis_files(&files, Level::DEBUG);
+
+    let mut disk = files
+        .iter()
+        .map(|(_, blocks)| blocks.clone())
+        .collect::<RangeSet<_>>();
+
+    // files get split up, so the pieces go in a new list
+    let mut moved_files = Vec::new();
+    for (id, mut blocks) in files.into_iter().rev() {
+        while let Some(free) = disk.first_gap(1, 0..blocks.start) {
+            let moved = blocks.len().min(free.len());
+            let from = blocks.end - moved..blocks.end;
+            let to = free.start..free.start + moved;
+            tracing::trace!("moving {} from {:?} to {:?}", id, from, to);
+
+            disk.remove(from);
+            disk.insert(to.clone());
+            moved_files.push((id, to));
+            blocks.end -= moved;
+
+            if blocks.is_empty() {
+                break;
+            }
         }
-        checksum += pos * id.unwrap();
+        moved_files.push((id, blocks));
     }
 
-    checksum
+    vis_files(&moved_files, Level::DEBUG);
+    checksum(&moved_files)
 }
 
-fn part2(data: &str) -> usize {
-    let mut map = size_space(data);
-    vis_size_space(&map, Level::DEBUG);
-
-    // if space at elt for last, then
-    //     move last
-    //     new elt is moved last
-    //     set space of new elt
-    //     new last is now the actual last
-    // new elt is next elt
-
-    let mut last = map.len() - 1;
-
-    'next_last: while last > 0 {
-        tracing::trace!("trying to place {}", map[last].id);
-        let mut first = 0;
-        while first < last {
-            tracing::trace!("trying to place {} after {}", map[last].id, map[first].id);
-            if map[first].space >= map[last].size {
-                map[last - 1].space += map[last].size + map[last].space;
-                map[last].space = map[first].space - map[last].size;
-                map[first].space = 0;
-                let last_item = map.remove(last);
-                map.insert(first + 1, last_item);
-                vis_size_space(&map, Level::TRACE);
-                continue 'next_last;
-            }
-            first += 1;
-        }
-        last -= 1;
-    }
+fn part2(data: &Input) -> usize {
+    let size_space = size_space(data.text());
+    vis_size_space(&size_space, Level::DEBUG);
 
-    let (map, _) = blocks(&map);
-    vis_blocks(&map, Level::DEBUG);
+    let mut files = layout(&size_space);
+    let mut disk = files
+        .iter()
+        .map(|(_, blocks)| blocks.clone())
+        .collect::<RangeSet<_>>();
 
-    let mut checksum = 0;
-    for (pos, id) in map.iter().enumerate() {
-        if id.is_none() {
+    for (id, blocks) in files.iter_mut().rev() {
+        let Some(free) = disk.first_gap(blocks.len(), 0..blocks.start) else {
             continue;
-        }
-        checksum += pos * id.unwrap();
+        };
+        let to = free.start..free.start + blocks.len();
+        tracing::trace!("moving {} from {:?} to {:?}", id, blocks, to);
+
+        disk.remove(blocks.clone());
+        disk.insert(to.clone());
+        *blocks = to;
     }
 
-    checksum
+    vis_files(&files, Level::DEBUG);
+    checksum(&files)
 }
 
 #[test]
 fn test1() {
-    assert_eq!(part1(include_str!("../data/day9.1.txt")), 6242766523059);
+    assert_eq!(
+        part1(&Input::new(include_str!("../data/day9.1.txt"))),
+        6242766523059
+    );
 }
 
 #[test]
 fn test2() {
-    assert_eq!(part2(include_str!("../data/day9.1.txt")), 6272188244509);
+    assert_eq!(
+        part2(&Input::new(include_str!("../data/day9.1.txt"))),
+        6272188244509
+    );
 }
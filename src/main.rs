@@ -3,18 +3,20 @@ use std::io::prelude::*;
 pub mod util;
 
 const TEMPLATE: &str = r#"
+use crate::util::Input;
+
 pub fn run(example: bool) {
-    let data = if example {
+    let data = Input::new(if example {
         include_str!("../data/example/day{day}.1.txt")
     } else {
         include_str!("../data/day{day}.1.txt")
-    };
+    });
 
     tracing::info!("day {day} part 1{}", if example { " example" } else { "" });
     tracing::info!(
         "day {day} part 1{} result: {}",
         if example { " example" } else { "" },
-        part1(data),
+        part1(&data),
     );
 
     if example {
@@ -25,26 +27,26 @@ pub fn run(example: bool) {
     tracing::info!(
         "day {day} part 2{} result: {}",
         if example { " example" } else { "" },
-        part2(data), // same data
+        part2(&data), // same data
     );
 }
 
-fn part1(data: &str) -> u64 {
+fn part1(data: &Input) -> u64 {
     0
 }
 
-fn part2(data: &str) -> u64 {
+fn part2(data: &Input) -> u64 {
     0
 }
 
 #[test]
 fn test1() {
-    assert_eq!(part1(include_str!("../data/day{day}.1.txt")), 0);
+    assert_eq!(part1(&Input::new(include_str!("../data/day{day}.1.txt"))), 0);
 }
 
 #[test]
 fn test2() {
-    assert_eq!(part2(include_str!("../data/day{day}.1.txt")), 0);
+    assert_eq!(part2(&Input::new(include_str!("../data/day{day}.1.txt"))), 0);
 }
 "#;
 
@@ -1,17 +1,60 @@
+pub mod automaton;
+pub mod components;
+pub mod counter;
+pub mod cycle;
+pub mod dense;
+pub mod digits;
+pub mod flood;
+pub mod grapheme;
+pub mod input;
+pub mod line;
+pub mod math;
+pub mod memo;
+pub mod nd;
+pub mod neighbourhood;
+pub mod order;
+pub mod pattern;
+pub mod range_set;
+pub mod region;
+pub mod scan;
+pub mod search;
+pub mod sparse;
+pub mod torus;
+pub mod transform;
+
 use std::{
-    collections::{BTreeSet, HashMap},
+    collections::{BTreeSet, HashMap, HashSet},
     fmt::Debug,
     hash::Hash,
 };
 
-pub fn frequency<K: Eq + Hash>(iter: impl Iterator<Item = K>) -> HashMap<K, u64> {
-    let mut counts = HashMap::new();
+pub use components::{label_components, Components, DisjointSet};
+pub use counter::Counter;
+pub use dense::{BitGrid, DenseMap};
+pub use flood::{flood_fill_with, Connectivity, Flood};
+pub use input::Input;
+pub use neighbourhood::Neighbourhood;
+pub use range_set::RangeSet;
+pub use sparse::SparseGrid;
 
-    for item in iter {
-        *counts.entry(item).or_default() += 1;
-    }
+pub type Grid<T> = Vec<Vec<T>>;
+
+/// std collections hashed with fnv, which is a lot quicker than siphash for the small keys
+/// puzzles use. nothing here needs protecting from hash flooding
+pub type FastMap<K, V> = HashMap<K, V, fnv::FnvBuildHasher>;
+pub type FastSet<T> = HashSet<T, fnv::FnvBuildHasher>;
+
+/// `tracing::trace!` for the innermost grid loops, only compiled in with the `trace-grid` feature
+macro_rules! grid_trace {
+    ($($arg:tt)*) => {{
+        #[cfg(feature = "trace-grid")]
+        tracing::trace!($($arg)*);
+    }};
+}
 
-    counts
+#[deprecated(note = "collect into a `Counter` instead")]
+pub fn frequency<K: Eq + Hash>(iter: impl Iterator<Item = K>) -> HashMap<K, u64> {
+    iter.collect::<Counter<_>>().into_iter().collect()
 }
 
 pub fn in_bounds_2d<T>(plane: &[Vec<T>], row: isize, col: isize) -> bool {
@@ -19,8 +62,9 @@ pub fn in_bounds_2d<T>(plane: &[Vec<T>], row: isize, col: isize) -> bool {
         && (0 <= col && col < plane[row as usize].len() as isize)
 }
 
+#[deprecated(note = "use `digits::count` instead")]
 pub fn num_digits(n: u64) -> u32 {
-    n.checked_ilog10().unwrap_or(0) + 1
+    digits::count(n)
 }
 
 /// ord doesn't make sense but i need it to put it in btreeset so shrug
@@ -46,18 +90,21 @@ impl Direction {
 
     pub const CARDINAL: [Direction; 4] = [Direction::N, Direction::S, Direction::E, Direction::W];
 
+    /// where it is in `Direction::ALL`
+    pub fn index(&self) -> usize {
+        *self as usize
+    }
+
+    /// `to_offset` of each direction, in the same order as `Direction::ALL`
+    #[rustfmt::skip]
+    pub const OFFSETS: [(isize, isize); 8] = [
+        (-1, -1), (-1, 0), (-1, 1),
+        ( 0, -1),          ( 0, 1),
+        ( 1, -1), ( 1, 0), ( 1, 1),
+    ];
+
     pub fn to_offset(&self) -> (isize, isize) {
-        use Direction::*;
-        match self {
-            NW => (-1, -1),
-            N => (-1, 0),
-            NE => (-1, 1),
-            W => (0, -1),
-            E => (0, 1),
-            SW => (1, -1),
-            S => (1, 0),
-            SE => (1, 1),
-        }
+        Direction::OFFSETS[self.index()]
     }
 
     pub fn apply_index<T>(
@@ -84,6 +131,21 @@ impl Direction {
         Some(&plane[new_row][new_col])
     }
 
+    /// like `apply_index` but off one edge comes back on the other, see `torus`
+    pub fn apply_index_wrapping<T>(
+        &self,
+        plane: &[Vec<T>],
+        row: usize,
+        col: usize,
+    ) -> (usize, usize) {
+        torus::Torus::new(plane).step((row, col), *self)
+    }
+
+    pub fn apply_wrapping<'a, T>(&self, plane: &'a [Vec<T>], row: usize, col: usize) -> &'a T {
+        let (new_row, new_col) = self.apply_index_wrapping(plane, row, col);
+        &plane[new_row][new_col]
+    }
+
     pub fn right_90(&self) -> Direction {
         use Direction::*;
         match self {
@@ -113,29 +175,35 @@ impl<'a, T: Debug> Iterator for Surrounding<'a, T> {
     type Item = SurroundingItem<'a, T>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.dir >= self.dirs.len() {
-            tracing::trace!("no more dirs surrounding {},{}", self.row, self.col);
-            return None;
-        }
+        while let Some(&dir) = self.dirs.get(self.dir) {
+            self.dir += 1;
 
-        let dir = self.dirs[self.dir];
-        self.dir += 1;
-
-        if let Some((off_row, off_col)) = dir.apply_index(self.plane, self.row, self.col) {
-            tracing::trace!(
-                "{:?} {},{} is {:?} of {},{}",
-                self.plane[off_row][off_col],
-                off_row,
-                off_col,
-                dir,
-                self.row,
-                self.col
-            );
-            Some((&self.plane[off_row][off_col], off_row, off_col, dir))
-        } else {
-            tracing::trace!("nothing is {:?} of {},{}", dir, self.row, self.col);
-            self.next()
+            // going off the top or left wraps round to something huge, which `get` turns down
+            let (row_off, col_off) = dir.to_offset();
+            let off_row = self.row.wrapping_add_signed(row_off);
+            let off_col = self.col.wrapping_add_signed(col_off);
+
+            if let Some(item) = self.plane.get(off_row).and_then(|lat| lat.get(off_col)) {
+                grid_trace!(
+                    "{:?} {},{} is {:?} of {},{}",
+                    item,
+                    off_row,
+                    off_col,
+                    dir,
+                    self.row,
+                    self.col
+                );
+                return Some((item, off_row, off_col, dir));
+            }
+            grid_trace!("nothing is {:?} of {},{}", dir, self.row, self.col);
         }
+
+        grid_trace!("no more dirs surrounding {},{}", self.row, self.col);
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.dirs.len() - self.dir))
     }
 }
 
@@ -144,8 +212,8 @@ fn surrounding<'a, T: Debug>(
     row: usize,
     col: usize,
     dirs: &'static [Direction],
-) -> impl Iterator<Item = SurroundingItem<'a, T>> {
-    tracing::trace!(
+) -> Surrounding<'a, T> {
+    grid_trace!(
         "look for items surrounding {:?} {},{} ({:?})",
         plane[row][col],
         row,
@@ -182,24 +250,64 @@ pub fn flood_fill<T: Debug + PartialEq<T>>(
     row: usize,
     col: usize,
 ) -> BTreeSet<(usize, usize)> {
-    let mut seen = BTreeSet::new();
-    let mut region = BTreeSet::from([(row, col)]);
     let t = &plane[row][col];
+    flood::flood_fill_into(plane, (row, col), Connectivity::Four, None, |_, elt| {
+        elt == t
+    })
+    .reached
+}
+
+#[test]
+#[allow(deprecated)]
+fn frequency_still_counts() {
+    let counts = frequency("abcab".chars());
+    assert_eq!(counts, HashMap::from([('a', 2), ('b', 2), ('c', 1)]));
+}
+
+#[test]
+#[allow(deprecated)]
+fn num_digits_still_counts() {
+    assert_eq!([0, 9, 10, u64::MAX].map(num_digits), [1, 1, 2, 20]);
+}
 
-    let mut queue = vec![(row, col)];
-    while let Some((row, col)) = queue.pop() {
-        if seen.contains(&(row, col)) {
-            continue;
+#[test]
+#[ignore = "benchmark, run with cargo test --release bench_surrounding -- --ignored --nocapture"]
+fn bench_surrounding() {
+    use std::{hint::black_box, time::Instant};
+
+    let size = black_box(1000);
+    let plane = (0..size)
+        .map(|row| {
+            (0..size)
+                .map(|col| (row * 31 + col * 17) % 10)
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<Vec<usize>>>();
+
+    let now = Instant::now();
+    let mut by_hand = 0;
+    for row in 0..size {
+        for col in 0..size {
+            for (row_off, col_off) in Direction::OFFSETS {
+                let (next_row, next_col) = (row as isize + row_off, col as isize + col_off);
+                if in_bounds_2d(&plane, next_row, next_col) {
+                    by_hand += black_box(plane[next_row as usize][next_col as usize]);
+                }
+            }
         }
-        seen.insert((row, col));
-        region.insert((row, col));
+    }
+    println!("by hand: {} in {:?}", by_hand, now.elapsed());
 
-        for (elt, search_row, search_col, _) in surrounding_cardinal(plane, row, col) {
-            if elt == t {
-                queue.push((search_row, search_col));
+    let now = Instant::now();
+    let mut surrounding = 0;
+    for row in 0..size {
+        for col in 0..size {
+            for (next, _, _, _) in surrounding_all(&plane, row, col) {
+                surrounding += black_box(*next);
             }
         }
     }
+    println!("surrounding_all: {} in {:?}", surrounding, now.elapsed());
 
-    region
+    assert_eq!(by_hand, surrounding);
 }
@@ -0,0 +1,209 @@
+//! stepping a whole grid at once with a rule that only looks at each cell and what's around it
+
+use super::{cycle::Cycle, dense::BitGrid, surrounding_all, FastMap, Grid};
+use std::{fmt::Debug, hash::Hash};
+
+/// a grid and a rule for what each cell becomes, given what it is and its `surrounding_all`
+/// neighbours. cells on the edge just have fewer neighbours. the rule has to be pure, since
+/// in sparse mode it only gets asked about cells that could have changed
+pub struct Automaton<T, F> {
+    current: Grid<T>,
+    /// the generation before `current`, written over by the next step
+    next: Grid<T>,
+    rule: F,
+    generation: usize,
+    /// cells that changed last step, and their neighbours. None if every cell gets stepped
+    active: Option<BitGrid>,
+    /// where the step fills in the next `active`, kept so it doesn't allocate every step
+    scratch: Option<BitGrid>,
+}
+
+impl<T, F> Automaton<T, F>
+where
+    T: Clone + PartialEq + Debug,
+    F: FnMut(&T, &[&T]) -> T,
+{
+    pub fn new(grid: Grid<T>, rule: F) -> Self {
+        Automaton {
+            next: grid.clone(),
+            current: grid,
+            rule,
+            generation: 0,
+            active: None,
+            scratch: None,
+        }
+    }
+
+    /// only steps cells that might change, which is much quicker when most of the grid is
+    /// sitting still
+    pub fn sparse(grid: Grid<T>, rule: F) -> Self {
+        let mut active = BitGrid::for_plane(&grid, 1);
+        for (row, lat) in grid.iter().enumerate() {
+            for col in 0..lat.len() {
+                active.insert(row, col);
+            }
+        }
+
+        Automaton {
+            scratch: Some(BitGrid::for_plane(&grid, 1)),
+            active: Some(active),
+            ..Automaton::new(grid, rule)
+        }
+    }
+
+    pub fn grid(&self) -> &Grid<T> {
+        &self.current
+    }
+
+    pub fn into_grid(self) -> Grid<T> {
+        self.current
+    }
+
+    /// steps taken so far
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    /// one generation. returns how many cells changed
+    pub fn step(&mut self) -> usize {
+        let mut changed = 0;
+        let mut around = Vec::with_capacity(8);
+        let mut next_active = self.scratch.as_mut();
+        if let Some(next_active) = next_active.as_mut() {
+            next_active.clear();
+        }
+
+        let mut visit = |row: usize, col: usize| {
+            around.clear();
+            around.extend(surrounding_all(&self.current, row, col).map(|(value, ..)| value));
+            let cell = (self.rule)(&self.current[row][col], &around);
+
+            if cell != self.current[row][col] {
+                changed += 1;
+                if let Some(next_active) = next_active.as_mut() {
+                    next_active.insert(row, col);
+                    for (_, next_row, next_col, _) in surrounding_all(&self.current, row, col) {
+                        next_active.insert(next_row, next_col);
+                    }
+                }
+            }
+            self.next[row][col] = cell;
+        };
+
+        // a cell that isn't active didn't change last step, so `next` still has it right
+        match &self.active {
+            Some(active) => active.iter().for_each(|(_, row, col)| visit(row, col)),
+            None => {
+                for row in 0..self.current.len() {
+                    for col in 0..self.current[row].len() {
+                        visit(row, col);
+                    }
+                }
+            }
+        }
+
+        std::mem::swap(&mut self.current, &mut self.next);
+        if let (Some(active), Some(scratch)) = (self.active.as_mut(), self.scratch.as_mut()) {
+            std::mem::swap(active, scratch);
+        }
+        self.generation += 1;
+        changed
+    }
+
+    pub fn run(&mut self, steps: usize) {
+        for _ in 0..steps {
+            self.step();
+        }
+    }
+
+    /// steps until nothing changes, giving the generation it settled on. None if it's still
+    /// going after `limit` steps
+    pub fn run_until_stable(&mut self, limit: usize) -> Option<usize> {
+        for _ in 0..limit {
+            if self.step() == 0 {
+                // the step that changed nothing ended up where it started
+                return Some(self.generation - 1);
+            }
+        }
+        None
+    }
+
+    /// steps until a grid comes round again. generations are counted from when this was
+    /// called, and it's left at `start + length`. a stable grid is a cycle of length 1
+    pub fn find_cycle(&mut self) -> Cycle
+    where
+        T: Eq + Hash,
+    {
+        let first = self.generation;
+        let mut seen = FastMap::default();
+        loop {
+            if let Some(start) = seen.insert(self.current.clone(), self.generation - first) {
+                let cycle = Cycle {
+                    start,
+                    length: self.generation - first - start,
+                };
+                tracing::debug!("automaton {:?}", cycle);
+                return cycle;
+            }
+            self.step();
+        }
+    }
+}
+
+#[cfg(test)]
+fn life(cell: &bool, around: &[&bool]) -> bool {
+    let alive = around.iter().filter(|&&&alive| alive).count();
+    alive == 3 || (*cell && alive == 2)
+}
+
+#[cfg(test)]
+fn cells(lines: &[&str]) -> Grid<bool> {
+    lines
+        .iter()
+        .map(|line| line.chars().map(|c| c == '#').collect())
+        .collect()
+}
+
+#[test]
+fn still_and_blinking() {
+    let mut block = Automaton::new(cells(&["....", ".##.", ".##.", "...."]), life);
+    assert_eq!(block.run_until_stable(10), Some(0));
+
+    let mut blinker = Automaton::new(cells(&[".....", "..#..", "..#..", "..#..", "....."]), life);
+    assert_eq!(blinker.run_until_stable(10), None);
+    assert_eq!(
+        blinker.find_cycle(),
+        Cycle {
+            start: 0,
+            length: 2
+        }
+    );
+
+    // three in an L fill in to a block after one step
+    let mut corner = Automaton::new(cells(&["....", ".#..", ".##.", "...."]), life);
+    assert_eq!(corner.step(), 1);
+    assert_eq!(corner.grid(), &cells(&["....", ".##.", ".##.", "...."]));
+}
+
+#[test]
+fn sparse_matches_dense() {
+    let glider = cells(&[
+        ".#........",
+        "..#.......",
+        "###.......",
+        "..........",
+        "..........",
+        "..........",
+        "......##..",
+        "......##..",
+    ]);
+    let mut dense = Automaton::new(glider.clone(), life);
+    let mut sparse = Automaton::sparse(glider, life);
+    for _ in 0..20 {
+        assert_eq!(dense.step(), sparse.step());
+        assert_eq!(dense.grid(), sparse.grid());
+    }
+    // crashes into the block and everything settles down
+    assert_eq!(sparse.run_until_stable(100), dense.run_until_stable(100));
+    assert!(sparse.generation() < 120);
+}
@@ -0,0 +1,269 @@
+use super::{Direction, Grid};
+
+/// union-find over `0..len`, with path compression and union by rank
+#[derive(Debug, Clone, Default)]
+pub struct DisjointSet {
+    parent: Vec<usize>,
+    rank: Vec<u8>,
+    size: Vec<usize>,
+    sets: usize,
+}
+
+impl DisjointSet {
+    /// every element starts out in its own set
+    pub fn new(len: usize) -> Self {
+        DisjointSet {
+            parent: (0..len).collect(),
+            rank: vec![0; len],
+            size: vec![1; len],
+            sets: len,
+        }
+    }
+
+    /// add a new element in its own set and return it
+    pub fn push(&mut self) -> usize {
+        let element = self.parent.len();
+        self.parent.push(element);
+        self.rank.push(0);
+        self.size.push(1);
+        self.sets += 1;
+        element
+    }
+
+    /// number of elements
+    pub fn len(&self) -> usize {
+        self.parent.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parent.is_empty()
+    }
+
+    /// number of separate sets
+    pub fn sets(&self) -> usize {
+        self.sets
+    }
+
+    /// the representative of `element`'s set
+    pub fn find(&mut self, element: usize) -> usize {
+        let mut root = element;
+        while self.parent[root] != root {
+            root = self.parent[root];
+        }
+
+        let mut element = element;
+        while self.parent[element] != root {
+            let next = self.parent[element];
+            self.parent[element] = root;
+            element = next;
+        }
+
+        root
+    }
+
+    /// false if they were already together
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (a, b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+
+        let (big, small) = if self.rank[a] < self.rank[b] {
+            (b, a)
+        } else {
+            (a, b)
+        };
+        self.parent[small] = big;
+        self.size[big] += self.size[small];
+        if self.rank[big] == self.rank[small] {
+            self.rank[big] += 1;
+        }
+        self.sets -= 1;
+
+        true
+    }
+
+    pub fn same(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    /// how many elements are in the same set as `element`
+    pub fn size_of(&mut self, element: usize) -> usize {
+        let root = self.find(element);
+        self.size[root]
+    }
+}
+
+/// what `label_components` found out about one component
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Component {
+    /// first cell in reading order
+    pub first: (usize, usize),
+    pub area: usize,
+    /// cell edges that don't touch another cell of the same component, including the grid edge
+    pub perimeter: usize,
+    pub top_left: (usize, usize),
+    /// inclusive
+    pub bottom_right: (usize, usize),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Components {
+    /// which component each cell is in, numbered in reading order of their first cell
+    pub labels: Grid<usize>,
+    pub stats: Vec<Component>,
+}
+
+impl Components {
+    /// number of components
+    pub fn len(&self) -> usize {
+        self.stats.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.stats.is_empty()
+    }
+
+    pub fn label(&self, row: usize, col: usize) -> usize {
+        self.labels[row][col]
+    }
+
+    /// every cell of the component, in reading order
+    pub fn cells(&self, label: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        let Component {
+            top_left: (top, left),
+            bottom_right: (bottom, right),
+            ..
+        } = self.stats[label];
+        (top..=bottom)
+            .flat_map(move |row| (left..=right).map(move |col| (row, col)))
+            .filter(move |&(row, col)| self.labels[row].get(col) == Some(&label))
+    }
+}
+
+/// group cardinally adjacent cells where `same` says so into components. `same` should be an
+/// equivalence, like `==`
+pub fn label_components<T>(grid: &[Vec<T>], mut same: impl FnMut(&T, &T) -> bool) -> Components {
+    // rows can be different lengths
+    let mut row_starts = Vec::with_capacity(grid.len() + 1);
+    row_starts.push(0);
+    for row in grid {
+        row_starts.push(row_starts.last().unwrap() + row.len());
+    }
+    let index = |row: usize, col: usize| row_starts[row] + col;
+
+    let mut sets = DisjointSet::new(*row_starts.last().unwrap());
+    for (row, lat) in grid.iter().enumerate() {
+        for (col, cell) in lat.iter().enumerate() {
+            if col + 1 < lat.len() && same(cell, &lat[col + 1]) {
+                sets.union(index(row, col), index(row, col + 1));
+            }
+            if grid.get(row + 1).is_some_and(|below| col < below.len())
+                && same(cell, &grid[row + 1][col])
+            {
+                sets.union(index(row, col), index(row + 1, col));
+            }
+        }
+    }
+
+    let mut root_labels = vec![None; sets.len()];
+    let mut stats = Vec::<Component>::with_capacity(sets.sets());
+    let mut labels = Vec::with_capacity(grid.len());
+    for (row, lat) in grid.iter().enumerate() {
+        let mut label_row = Vec::with_capacity(lat.len());
+        for col in 0..lat.len() {
+            let root = sets.find(index(row, col));
+            let label = *root_labels[root].get_or_insert_with(|| {
+                stats.push(Component {
+                    first: (row, col),
+                    area: 0,
+                    perimeter: 0,
+                    top_left: (row, col),
+                    bottom_right: (row, col),
+                });
+                stats.len() - 1
+            });
+
+            let together = Direction::CARDINAL
+                .iter()
+                .filter_map(|dir| dir.apply_index(grid, row, col))
+                .filter(|&(other_row, other_col)| sets.find(index(other_row, other_col)) == root)
+                .count();
+
+            let component = &mut stats[label];
+            component.area += 1;
+            component.perimeter += 4 - together;
+            component.top_left.1 = component.top_left.1.min(col);
+            component.bottom_right.0 = row;
+            component.bottom_right.1 = component.bottom_right.1.max(col);
+
+            label_row.push(label);
+        }
+        labels.push(label_row);
+    }
+
+    Components { labels, stats }
+}
+
+#[test]
+fn disjoint_set() {
+    let mut sets = DisjointSet::new(6);
+    assert!(sets.union(0, 1));
+    assert!(sets.union(2, 3));
+    assert!(sets.union(1, 3));
+    assert!(!sets.union(0, 2));
+    assert!(sets.same(0, 3) && !sets.same(0, 4));
+    assert_eq!((sets.sets(), sets.size_of(2)), (3, 4));
+
+    let added = sets.push();
+    sets.union(added, 5);
+    assert_eq!((sets.len(), sets.sets(), sets.size_of(5)), (7, 3, 2));
+}
+
+#[test]
+fn components() {
+    let grid = ["AAAA", "BBCD", "BBCC", "EEEC"]
+        .iter()
+        .map(|line| line.chars().collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    let components = label_components(&grid, |a, b| a == b);
+
+    assert_eq!(components.len(), 5);
+    assert_eq!(
+        components.labels,
+        vec![
+            vec![0, 0, 0, 0],
+            vec![1, 1, 2, 3],
+            vec![1, 1, 2, 2],
+            vec![4, 4, 4, 2],
+        ]
+    );
+    assert_eq!(
+        components
+            .stats
+            .iter()
+            .map(|c| (c.area, c.perimeter))
+            .collect::<Vec<_>>(),
+        vec![(4, 10), (4, 8), (4, 10), (1, 4), (3, 8)]
+    );
+    assert_eq!(
+        (
+            components.stats[2].top_left,
+            components.stats[2].bottom_right
+        ),
+        ((1, 2), (3, 3))
+    );
+    assert_eq!(
+        components.cells(2).collect::<Vec<_>>(),
+        vec![(1, 2), (2, 2), (2, 3), (3, 3)]
+    );
+}
+
+#[test]
+fn not_equality() {
+    // the two zeros only touch diagonally, so they stay apart
+    let grid = vec![vec![1, 1, 1], vec![1, 0, 1], vec![1, 1, 0]];
+    let components = label_components(&grid, |a, b| (a > &0) == (b > &0));
+    assert_eq!(components.len(), 3);
+    assert_eq!(components.stats[0].perimeter, 16);
+}
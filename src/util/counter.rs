@@ -0,0 +1,199 @@
+use super::FastMap;
+use std::{
+    hash::Hash,
+    ops::{Add, AddAssign, Index, Sub, SubAssign},
+};
+
+/// how many of each thing there are. anything not in the counter has a count of zero
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Counter<K: Eq + Hash> {
+    counts: FastMap<K, u64>,
+}
+
+impl<K: Eq + Hash> Default for Counter<K> {
+    fn default() -> Self {
+        Counter {
+            counts: FastMap::default(),
+        }
+    }
+}
+
+impl<K: Eq + Hash> Counter<K> {
+    pub fn new() -> Self {
+        Counter::default()
+    }
+
+    /// from `(thing, how many)` pairs. repeated things are summed
+    pub fn from_weighted(iter: impl IntoIterator<Item = (K, u64)>) -> Self {
+        let mut counter = Counter::new();
+        counter.extend_weighted(iter);
+        counter
+    }
+
+    pub fn extend_weighted(&mut self, iter: impl IntoIterator<Item = (K, u64)>) {
+        for (key, n) in iter {
+            self.add(key, n);
+        }
+    }
+
+    pub fn get(&self, key: &K) -> u64 {
+        self.counts.get(key).copied().unwrap_or(0)
+    }
+
+    pub fn add(&mut self, key: K, n: u64) {
+        if n != 0 {
+            *self.counts.entry(key).or_default() += n;
+        }
+    }
+
+    /// takes away at most as many as there are, and forgets things that hit zero
+    pub fn remove(&mut self, key: &K, n: u64) {
+        if let Some(count) = self.counts.get_mut(key) {
+            *count = count.saturating_sub(n);
+            if *count == 0 {
+                self.counts.remove(key);
+            }
+        }
+    }
+
+    pub fn merge(&mut self, other: &Counter<K>)
+    where
+        K: Clone,
+    {
+        for (key, n) in other.iter() {
+            self.add(key.clone(), n);
+        }
+    }
+
+    pub fn subtract(&mut self, other: &Counter<K>) {
+        for (key, n) in other.iter() {
+            self.remove(key, n);
+        }
+    }
+
+    /// sum of every count
+    pub fn total(&self) -> u64 {
+        self.counts.values().sum()
+    }
+
+    /// number of different things
+    pub fn len(&self) -> usize {
+        self.counts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty()
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &K> {
+        self.counts.keys()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&K, u64)> {
+        self.counts.iter().map(|(key, n)| (key, *n))
+    }
+}
+
+impl<K: Eq + Hash + Ord> Counter<K> {
+    /// biggest counts first, ties broken by the smaller key
+    pub fn sorted(&self) -> Vec<(&K, u64)> {
+        let mut sorted = self.iter().collect::<Vec<_>>();
+        sorted.sort_by(|(a_key, a_n), (b_key, b_n)| b_n.cmp(a_n).then(a_key.cmp(b_key)));
+        sorted
+    }
+
+    pub fn most_common(&self, n: usize) -> Vec<(&K, u64)> {
+        let mut sorted = self.sorted();
+        sorted.truncate(n);
+        sorted
+    }
+}
+
+impl<K: Eq + Hash> FromIterator<K> for Counter<K> {
+    fn from_iter<I: IntoIterator<Item = K>>(iter: I) -> Self {
+        let mut counter = Counter::new();
+        counter.extend(iter);
+        counter
+    }
+}
+
+impl<K: Eq + Hash> Extend<K> for Counter<K> {
+    fn extend<I: IntoIterator<Item = K>>(&mut self, iter: I) {
+        for key in iter {
+            self.add(key, 1);
+        }
+    }
+}
+
+impl<K: Eq + Hash> IntoIterator for Counter<K> {
+    type Item = (K, u64);
+    type IntoIter = std::collections::hash_map::IntoIter<K, u64>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.counts.into_iter()
+    }
+}
+
+impl<K: Eq + Hash> Index<&K> for Counter<K> {
+    type Output = u64;
+
+    fn index(&self, key: &K) -> &Self::Output {
+        self.counts.get(key).unwrap_or(&0)
+    }
+}
+
+impl<K: Eq + Hash + Clone> AddAssign<&Counter<K>> for Counter<K> {
+    fn add_assign(&mut self, rhs: &Counter<K>) {
+        self.merge(rhs);
+    }
+}
+
+impl<K: Eq + Hash + Clone> Add<&Counter<K>> for Counter<K> {
+    type Output = Counter<K>;
+
+    fn add(mut self, rhs: &Counter<K>) -> Self::Output {
+        self += rhs;
+        self
+    }
+}
+
+impl<K: Eq + Hash> SubAssign<&Counter<K>> for Counter<K> {
+    fn sub_assign(&mut self, rhs: &Counter<K>) {
+        self.subtract(rhs);
+    }
+}
+
+impl<K: Eq + Hash> Sub<&Counter<K>> for Counter<K> {
+    type Output = Counter<K>;
+
+    fn sub(mut self, rhs: &Counter<K>) -> Self::Output {
+        self -= rhs;
+        self
+    }
+}
+
+#[test]
+fn counting() {
+    let letters = "mississippi".chars().collect::<Counter<_>>();
+    assert_eq!(letters[&'s'], 4);
+    assert_eq!(letters.get(&'z'), 0);
+    assert_eq!(letters.total(), 11);
+    assert_eq!(letters.most_common(2), vec![(&'i', 4), (&'s', 4)]);
+    assert_eq!(
+        letters.sorted(),
+        vec![(&'i', 4), (&'s', 4), (&'p', 2), (&'m', 1)]
+    );
+}
+
+#[test]
+fn arithmetic() {
+    let a = Counter::from_weighted([(1, 3), (2, 1), (1, 2)]);
+    let b = [1, 2, 2, 3].into_iter().collect::<Counter<_>>();
+
+    let sum = a.clone() + &b;
+    assert_eq!((sum[&1], sum[&2], sum[&3]), (6, 3, 1));
+
+    let difference = a - &b;
+    assert_eq!((difference[&1], difference[&2]), (4, 0));
+    assert_eq!(difference.len(), 1);
+}
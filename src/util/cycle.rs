@@ -0,0 +1,160 @@
+use super::FastMap;
+use std::hash::Hash;
+
+/// the first state on the cycle is reached after `start` steps, and comes back every `length`
+/// steps after that
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cycle {
+    pub start: usize,
+    pub length: usize,
+}
+
+impl Cycle {
+    /// which step before the cycle or during its first lap has the same state as step `n`
+    pub fn equivalent_step(&self, n: usize) -> usize {
+        if n < self.start {
+            n
+        } else {
+            self.start + (n - self.start) % self.length
+        }
+    }
+}
+
+/// brent's algorithm. `step` must be pure, and the state space finite so a cycle exists. only
+/// keeps two states around
+pub fn brent<T: Clone + PartialEq>(start: T, mut step: impl FnMut(&T) -> T) -> Cycle {
+    let mut power = 1;
+    let mut length = 1;
+    let mut tortoise = start.clone();
+    let mut hare = step(&start);
+    while tortoise != hare {
+        if power == length {
+            tortoise = hare.clone();
+            power *= 2;
+            length = 0;
+        }
+        hare = step(&hare);
+        length += 1;
+    }
+
+    let mut tortoise = start.clone();
+    let mut hare = start;
+    for _ in 0..length {
+        hare = step(&hare);
+    }
+
+    let mut cycle_start = 0;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        cycle_start += 1;
+    }
+
+    Cycle {
+        start: cycle_start,
+        length,
+    }
+}
+
+/// floyd's tortoise and hare. same requirements as `brent`, usually a bit slower
+pub fn floyd<T: Clone + PartialEq>(start: T, mut step: impl FnMut(&T) -> T) -> Cycle {
+    let mut tortoise = step(&start);
+    let mut hare = step(&tortoise);
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        hare = step(&hare);
+    }
+
+    let mut cycle_start = 0;
+    let mut tortoise = start;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        cycle_start += 1;
+    }
+
+    let mut length = 1;
+    let mut hare = step(&tortoise);
+    while tortoise != hare {
+        hare = step(&hare);
+        length += 1;
+    }
+
+    Cycle {
+        start: cycle_start,
+        length,
+    }
+}
+
+/// remembers every state it has seen. `step` returning None means the simulation ended, in which
+/// case there's no cycle
+pub fn detect<S: Clone + Eq + Hash>(
+    start: S,
+    mut step: impl FnMut(&S) -> Option<S>,
+) -> Option<Cycle> {
+    let mut seen = FastMap::default();
+    let mut state = start;
+    for i in 0.. {
+        if let Some(first) = seen.insert(state.clone(), i) {
+            tracing::debug!("state at step {} first seen at step {}", i, first);
+            return Some(Cycle {
+                start: first,
+                length: i - first,
+            });
+        }
+        state = step(&state)?;
+    }
+    unreachable!()
+}
+
+/// the state after `n` steps, skipping whole laps once the simulation starts repeating
+pub fn nth_state<S: Clone + Eq + Hash>(start: S, n: usize, mut step: impl FnMut(&S) -> S) -> S {
+    let mut seen = FastMap::default();
+    let mut states = Vec::new();
+    let mut state = start;
+    while states.len() < n {
+        if let Some(first) = seen.insert(state.clone(), states.len()) {
+            let cycle = Cycle {
+                start: first,
+                length: states.len() - first,
+            };
+            tracing::debug!("fast forward {:?}", cycle);
+            return states.swap_remove(cycle.equivalent_step(n));
+        }
+        let next = step(&state);
+        states.push(state);
+        state = next;
+    }
+    state
+}
+
+#[test]
+fn rho() {
+    // 0 1 2 3 4 5 6 4 5 6 ...
+    let step = |x: &u32| if *x == 6 { 4 } else { x + 1 };
+    let expected = Cycle {
+        start: 4,
+        length: 3,
+    };
+    assert_eq!(brent(0, step), expected);
+    assert_eq!(floyd(0, step), expected);
+    assert_eq!(detect(0, |x| Some(step(x))), Some(expected));
+    assert_eq!(detect(0, |x| if *x < 5 { Some(x + 1) } else { None }), None);
+
+    assert_eq!(nth_state(0, 3, step), 3);
+    assert_eq!(nth_state(0, 7, step), 4);
+    assert_eq!(nth_state(0, 1_000_000_000, step), 4);
+}
+
+#[test]
+fn pure_cycle() {
+    let step = |x: &u64| x * 7 % 11;
+    let expected = Cycle {
+        start: 0,
+        length: 10,
+    };
+    assert_eq!(brent(1, step), expected);
+    assert_eq!(floyd(1, step), expected);
+    assert_eq!(nth_state(1, 1_000_000_000_005, step), 10);
+}
@@ -0,0 +1,327 @@
+//! sets and maps over grid positions, stored flat at `(layer * height + row) * width + col`.
+//! layers are for things like the direction something was facing in a cell
+
+use super::Grid;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Layout {
+    height: usize,
+    width: usize,
+    layers: usize,
+}
+
+impl Layout {
+    fn len(&self) -> usize {
+        self.layers * self.height * self.width
+    }
+
+    fn index(&self, layer: usize, row: usize, col: usize) -> usize {
+        assert!(
+            layer < self.layers && row < self.height && col < self.width,
+            "{},{} in layer {} is outside {}x{}x{}",
+            row,
+            col,
+            layer,
+            self.height,
+            self.width,
+            self.layers
+        );
+        (layer * self.height + row) * self.width + col
+    }
+
+    fn position(&self, index: usize) -> (usize, usize, usize) {
+        let col = index % self.width;
+        let row = index / self.width % self.height;
+        let layer = index / self.width / self.height;
+        (layer, row, col)
+    }
+
+    /// as big as the widest row
+    fn for_plane<T>(plane: &[Vec<T>], layers: usize) -> Self {
+        Layout {
+            height: plane.len(),
+            width: plane.iter().map(Vec::len).max().unwrap_or(0),
+            layers,
+        }
+    }
+}
+
+/// a set of grid positions, one bit each
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BitGrid {
+    layout: Layout,
+    bits: Vec<u64>,
+}
+
+impl BitGrid {
+    pub fn new(height: usize, width: usize) -> Self {
+        BitGrid::with_layers(height, width, 1)
+    }
+
+    pub fn with_layers(height: usize, width: usize, layers: usize) -> Self {
+        BitGrid::from_layout(Layout {
+            height,
+            width,
+            layers,
+        })
+    }
+
+    /// big enough for any position in `plane`
+    pub fn for_plane<T>(plane: &[Vec<T>], layers: usize) -> Self {
+        BitGrid::from_layout(Layout::for_plane(plane, layers))
+    }
+
+    fn from_layout(layout: Layout) -> Self {
+        BitGrid {
+            bits: vec![0; layout.len().div_ceil(64)],
+            layout,
+        }
+    }
+
+    pub fn contains(&self, row: usize, col: usize) -> bool {
+        self.contains_in(0, row, col)
+    }
+
+    /// true if it wasn't already there
+    pub fn insert(&mut self, row: usize, col: usize) -> bool {
+        self.insert_in(0, row, col)
+    }
+
+    /// true if it was there
+    pub fn remove(&mut self, row: usize, col: usize) -> bool {
+        self.remove_in(0, row, col)
+    }
+
+    pub fn contains_in(&self, layer: usize, row: usize, col: usize) -> bool {
+        let index = self.layout.index(layer, row, col);
+        self.bits[index / 64] & (1 << (index % 64)) != 0
+    }
+
+    pub fn insert_in(&mut self, layer: usize, row: usize, col: usize) -> bool {
+        let index = self.layout.index(layer, row, col);
+        let word = &mut self.bits[index / 64];
+        let bit = 1 << (index % 64);
+        let new = *word & bit == 0;
+        *word |= bit;
+        new
+    }
+
+    pub fn remove_in(&mut self, layer: usize, row: usize, col: usize) -> bool {
+        let index = self.layout.index(layer, row, col);
+        let word = &mut self.bits[index / 64];
+        let bit = 1 << (index % 64);
+        let was = *word & bit != 0;
+        *word &= !bit;
+        was
+    }
+
+    /// number of positions in the set, counting each layer separately
+    pub fn len(&self) -> usize {
+        self.bits
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bits.iter().all(|&word| word == 0)
+    }
+
+    /// keeps the memory around so it can be reused
+    pub fn clear(&mut self) {
+        self.bits.fill(0);
+    }
+
+    /// `(layer, row, col)` of everything in the set, in index order
+    pub fn iter(&self) -> impl Iterator<Item = (usize, usize, usize)> + '_ {
+        self.bits
+            .iter()
+            .enumerate()
+            .flat_map(|(word_index, &word)| {
+                let mut word = word;
+                std::iter::from_fn(move || {
+                    if word == 0 {
+                        return None;
+                    }
+                    let bit = word.trailing_zeros() as usize;
+                    word &= word - 1;
+                    Some(word_index * 64 + bit)
+                })
+            })
+            .map(|index| self.layout.position(index))
+    }
+
+    /// `(row, col)` of everything in any layer, each once, in reading order
+    pub fn positions(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        (0..self.layout.height).flat_map(move |row| {
+            (0..self.layout.width).filter_map(move |col| {
+                (0..self.layout.layers)
+                    .any(|layer| self.contains_in(layer, row, col))
+                    .then_some((row, col))
+            })
+        })
+    }
+}
+
+/// a map from grid positions to values, with a slot for every position
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DenseMap<T> {
+    layout: Layout,
+    values: Vec<Option<T>>,
+    len: usize,
+}
+
+impl<T> DenseMap<T> {
+    pub fn new(height: usize, width: usize) -> Self {
+        DenseMap::with_layers(height, width, 1)
+    }
+
+    pub fn with_layers(height: usize, width: usize, layers: usize) -> Self {
+        DenseMap::from_layout(Layout {
+            height,
+            width,
+            layers,
+        })
+    }
+
+    pub fn for_plane<U>(plane: &[Vec<U>], layers: usize) -> Self {
+        DenseMap::from_layout(Layout::for_plane(plane, layers))
+    }
+
+    fn from_layout(layout: Layout) -> Self {
+        DenseMap {
+            values: std::iter::repeat_with(|| None).take(layout.len()).collect(),
+            layout,
+            len: 0,
+        }
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> Option<&T> {
+        self.get_in(0, row, col)
+    }
+
+    pub fn get_mut(&mut self, row: usize, col: usize) -> Option<&mut T> {
+        self.get_mut_in(0, row, col)
+    }
+
+    /// the old value if there was one
+    pub fn insert(&mut self, row: usize, col: usize, value: T) -> Option<T> {
+        self.insert_in(0, row, col, value)
+    }
+
+    pub fn remove(&mut self, row: usize, col: usize) -> Option<T> {
+        self.remove_in(0, row, col)
+    }
+
+    pub fn contains(&self, row: usize, col: usize) -> bool {
+        self.get(row, col).is_some()
+    }
+
+    pub fn get_in(&self, layer: usize, row: usize, col: usize) -> Option<&T> {
+        self.values[self.layout.index(layer, row, col)].as_ref()
+    }
+
+    pub fn get_mut_in(&mut self, layer: usize, row: usize, col: usize) -> Option<&mut T> {
+        let index = self.layout.index(layer, row, col);
+        self.values[index].as_mut()
+    }
+
+    pub fn insert_in(&mut self, layer: usize, row: usize, col: usize, value: T) -> Option<T> {
+        let index = self.layout.index(layer, row, col);
+        let old = self.values[index].replace(value);
+        if old.is_none() {
+            self.len += 1;
+        }
+        old
+    }
+
+    pub fn remove_in(&mut self, layer: usize, row: usize, col: usize) -> Option<T> {
+        let index = self.layout.index(layer, row, col);
+        let old = self.values[index].take();
+        if old.is_some() {
+            self.len -= 1;
+        }
+        old
+    }
+
+    pub fn get_or_insert_with(&mut self, row: usize, col: usize, f: impl FnOnce() -> T) -> &mut T {
+        let index = self.layout.index(0, row, col);
+        if self.values[index].is_none() {
+            self.len += 1;
+        }
+        self.values[index].get_or_insert_with(f)
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn clear(&mut self) {
+        self.values.iter_mut().for_each(|value| *value = None);
+        self.len = 0;
+    }
+
+    /// `((layer, row, col), value)` in index order
+    pub fn iter(&self) -> impl Iterator<Item = ((usize, usize, usize), &T)> + '_ {
+        self.values
+            .iter()
+            .enumerate()
+            .filter_map(|(index, value)| Some((self.layout.position(index), value.as_ref()?)))
+    }
+
+    /// the first layer as a grid
+    pub fn to_grid(&self) -> Grid<Option<T>>
+    where
+        T: Clone,
+    {
+        self.values[..self.layout.height * self.layout.width]
+            .chunks(self.layout.width.max(1))
+            .map(<[Option<T>]>::to_vec)
+            .collect()
+    }
+}
+
+#[test]
+fn bits() {
+    let mut grid = BitGrid::with_layers(3, 50, 4);
+    assert!(grid.insert(2, 49));
+    assert!(!grid.insert(2, 49));
+    assert!(grid.insert_in(3, 0, 1));
+    assert!(grid.insert_in(1, 0, 1));
+    assert!(grid.contains_in(3, 0, 1) && !grid.contains(0, 1));
+    assert_eq!(grid.len(), 3);
+    assert_eq!(
+        grid.iter().collect::<Vec<_>>(),
+        vec![(0, 2, 49), (1, 0, 1), (3, 0, 1)]
+    );
+    assert_eq!(grid.positions().collect::<Vec<_>>(), vec![(0, 1), (2, 49)]);
+
+    assert!(grid.remove_in(1, 0, 1) && !grid.remove_in(1, 0, 1));
+    grid.clear();
+    assert!(grid.is_empty());
+}
+
+#[test]
+fn map() {
+    let mut map = DenseMap::new(2, 3);
+    assert_eq!(map.insert(1, 2, 'a'), None);
+    assert_eq!(map.insert(1, 2, 'b'), Some('a'));
+    *map.get_or_insert_with(0, 0, || 'c') = 'd';
+    assert_eq!(map.len(), 2);
+    assert_eq!(
+        map.to_grid(),
+        vec![vec![Some('d'), None, None], vec![None, None, Some('b')]]
+    );
+    assert_eq!(map.remove(0, 0), Some('d'));
+    assert_eq!(map.iter().collect::<Vec<_>>(), vec![((0, 1, 2), &'b')]);
+}
+
+#[test]
+#[should_panic]
+fn out_of_bounds() {
+    BitGrid::new(2, 2).insert(0, 2);
+}
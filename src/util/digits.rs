@@ -0,0 +1,234 @@
+//! digit twiddling on `u64`s. the free functions are base 10, `Radix` does the same for any
+//! other base. anything that can overflow has a `checked_` version that gives None instead
+
+/// a number base, at least 2
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Radix(u64);
+
+impl Radix {
+    pub const BINARY: Radix = Radix(2);
+    pub const OCTAL: Radix = Radix(8);
+    pub const DECIMAL: Radix = Radix(10);
+    pub const HEX: Radix = Radix(16);
+
+    pub fn new(radix: u64) -> Self {
+        assert!(radix >= 2, "radix {} is too small", radix);
+        Radix(radix)
+    }
+
+    pub fn get(&self) -> u64 {
+        self.0
+    }
+
+    /// how many digits `n` has. zero has one
+    pub fn count(&self, n: u64) -> u32 {
+        n.checked_ilog(self.0).unwrap_or(0) + 1
+    }
+
+    /// most significant digit first, or use `.rev()` for least significant first
+    pub fn digits(&self, n: u64) -> Digits {
+        Digits {
+            value: n,
+            radix: self.0,
+            len: self.count(n),
+        }
+    }
+
+    /// the number with these digits, most significant first. None if it doesn't fit or if a
+    /// digit is too big for the radix
+    pub fn checked_from_digits(&self, digits: impl IntoIterator<Item = u64>) -> Option<u64> {
+        digits.into_iter().try_fold(0u64, |acc, digit| {
+            (digit < self.0).then_some(())?;
+            acc.checked_mul(self.0)?.checked_add(digit)
+        })
+    }
+
+    pub fn from_digits(&self, digits: impl IntoIterator<Item = u64>) -> u64 {
+        self.checked_from_digits(digits)
+            .expect("digits don't make a u64")
+    }
+
+    /// the digits of `left` followed by the digits of `right`. a `left` of zero has no digits to
+    /// put in front, so it's just `right`
+    pub fn checked_concat(&self, left: u64, right: u64) -> Option<u64> {
+        if left == 0 {
+            // the shift for a long `right` might not fit even though nothing needs shifting
+            return Some(right);
+        }
+        left.checked_mul(self.0.checked_pow(self.count(right))?)?
+            .checked_add(right)
+    }
+
+    pub fn concat(&self, left: u64, right: u64) -> u64 {
+        self.checked_concat(left, right)
+            .unwrap_or_else(|| panic!("concatenating {} and {} overflowed", left, right))
+    }
+
+    /// the first `at` digits and the rest. leading zeros of the right half get lost, so 1000
+    /// split at 2 is (10, 0). None if `n` doesn't have `at` digits
+    pub fn checked_split(&self, n: u64, at: u32) -> Option<(u64, u64)> {
+        let count = self.count(n);
+        if at > count {
+            return None;
+        }
+        if at == 0 {
+            return Some((0, n));
+        }
+        // less than n, so it fits
+        let place = self.0.pow(count - at);
+        Some((n / place, n % place))
+    }
+
+    pub fn split(&self, n: u64, at: u32) -> (u64, u64) {
+        self.checked_split(n, at)
+            .unwrap_or_else(|| panic!("{} doesn't have {} digits to split at", n, at))
+    }
+
+    /// digits backwards. trailing zeros turn into leading zeros and disappear
+    pub fn checked_reverse(&self, n: u64) -> Option<u64> {
+        self.checked_from_digits(self.digits(n).rev())
+    }
+
+    pub fn reverse(&self, n: u64) -> u64 {
+        self.checked_reverse(n)
+            .unwrap_or_else(|| panic!("reversing {} overflowed", n))
+    }
+
+    /// can't overflow, the biggest possible sum is 64 binary ones
+    pub fn digit_sum(&self, n: u64) -> u64 {
+        self.digits(n).sum()
+    }
+}
+
+/// digits of a number, see `Radix::digits`
+#[derive(Debug, Clone)]
+pub struct Digits {
+    /// what's left once the digits already taken from either end are gone
+    value: u64,
+    radix: u64,
+    len: u32,
+}
+
+impl Iterator for Digits {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        self.len -= 1;
+        let place = self.radix.pow(self.len);
+        let digit = self.value / place;
+        self.value %= place;
+        Some(digit)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len as usize, Some(self.len as usize))
+    }
+}
+
+impl DoubleEndedIterator for Digits {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        self.len -= 1;
+        let digit = self.value % self.radix;
+        self.value /= self.radix;
+        Some(digit)
+    }
+}
+
+impl ExactSizeIterator for Digits {}
+
+pub fn count(n: u64) -> u32 {
+    Radix::DECIMAL.count(n)
+}
+
+pub fn digits(n: u64) -> Digits {
+    Radix::DECIMAL.digits(n)
+}
+
+pub fn checked_from_digits(digits: impl IntoIterator<Item = u64>) -> Option<u64> {
+    Radix::DECIMAL.checked_from_digits(digits)
+}
+
+pub fn from_digits(digits: impl IntoIterator<Item = u64>) -> u64 {
+    Radix::DECIMAL.from_digits(digits)
+}
+
+pub fn checked_concat(left: u64, right: u64) -> Option<u64> {
+    Radix::DECIMAL.checked_concat(left, right)
+}
+
+pub fn concat(left: u64, right: u64) -> u64 {
+    Radix::DECIMAL.concat(left, right)
+}
+
+pub fn checked_split(n: u64, at: u32) -> Option<(u64, u64)> {
+    Radix::DECIMAL.checked_split(n, at)
+}
+
+pub fn split(n: u64, at: u32) -> (u64, u64) {
+    Radix::DECIMAL.split(n, at)
+}
+
+pub fn checked_reverse(n: u64) -> Option<u64> {
+    Radix::DECIMAL.checked_reverse(n)
+}
+
+pub fn reverse(n: u64) -> u64 {
+    Radix::DECIMAL.reverse(n)
+}
+
+pub fn digit_sum(n: u64) -> u64 {
+    Radix::DECIMAL.digit_sum(n)
+}
+
+#[test]
+fn decimal() {
+    assert_eq!(count(0), 1);
+    assert_eq!(count(u64::MAX), 20);
+    assert_eq!(digits(1203).collect::<Vec<_>>(), vec![1, 2, 0, 3]);
+    assert_eq!(digits(1203).rev().collect::<Vec<_>>(), vec![3, 0, 2, 1]);
+
+    let mut both_ends = digits(12345);
+    assert_eq!(both_ends.next_back(), Some(5));
+    assert_eq!(both_ends.next(), Some(1));
+    assert_eq!(both_ends.collect::<Vec<_>>(), vec![2, 3, 4]);
+
+    assert_eq!(concat(15, 6), 156);
+    assert_eq!(concat(12, 0), 120);
+    assert_eq!(split(253000, 3), (253, 0));
+    assert_eq!(split(1234, 0), (0, 1234));
+    assert_eq!(split(u64::MAX, 0), (0, u64::MAX));
+    assert_eq!(reverse(1200), 21);
+    assert_eq!(digit_sum(99), 18);
+}
+
+#[test]
+fn overflow() {
+    assert_eq!(
+        checked_concat(u64::MAX / 10, 5),
+        Some(u64::MAX / 10 * 10 + 5)
+    );
+    assert_eq!(checked_concat(u64::MAX / 10, 6), None);
+    assert_eq!(checked_concat(2, 10u64.pow(19)), None);
+    assert_eq!(checked_concat(0, 10u64.pow(19)), Some(10u64.pow(19)));
+    assert_eq!(checked_concat(0, u64::MAX), Some(u64::MAX));
+    assert_eq!(checked_reverse(18_000_000_000_000_000_009), None);
+    assert_eq!(checked_split(123, 4), None);
+    assert_eq!(checked_from_digits([1, 10]), None);
+}
+
+#[test]
+fn radices() {
+    assert_eq!(Radix::BINARY.digits(6).collect::<Vec<_>>(), vec![1, 1, 0]);
+    assert_eq!(Radix::BINARY.count(u64::MAX), 64);
+    assert_eq!(Radix::BINARY.digit_sum(u64::MAX), 64);
+    assert_eq!(Radix::HEX.concat(0xab, 0xcd), 0xabcd);
+    assert_eq!(Radix::HEX.checked_concat(u64::MAX, 0), None);
+    assert_eq!(Radix::new(3).from_digits([2, 1, 0]), 21);
+    assert_eq!(Radix::OCTAL.split(0o1234, 1), (1, 0o234));
+}
@@ -0,0 +1,121 @@
+use super::{surrounding, Direction, FastSet, Grid};
+use std::{collections::VecDeque, fmt::Debug};
+
+/// which cells count as next to each other
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Connectivity {
+    /// up, down, left and right
+    Four,
+    /// diagonals too
+    Eight,
+}
+
+impl Connectivity {
+    pub fn directions(&self) -> &'static [Direction] {
+        match self {
+            Connectivity::Four => &Direction::CARDINAL,
+            Connectivity::Eight => &Direction::ALL,
+        }
+    }
+}
+
+/// where a flood fill got to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Flood<S = FastSet<(usize, usize)>> {
+    /// steps from the start, None if it never got there
+    pub distance: Grid<Option<usize>>,
+    pub reached: S,
+}
+
+impl<S> Flood<S> {
+    pub fn distance_to(&self, row: usize, col: usize) -> Option<usize> {
+        self.distance.get(row)?.get(col).copied().flatten()
+    }
+}
+
+/// breadth first from `start`, going from a cell to its neighbour when `passable(cell, neighbour)`
+/// says so, and stopping `max_depth` steps out if there is one
+pub fn flood_fill_with<T: Debug>(
+    plane: &[Vec<T>],
+    (row, col): (usize, usize),
+    connectivity: Connectivity,
+    max_depth: Option<usize>,
+    passable: impl FnMut(&T, &T) -> bool,
+) -> Flood {
+    flood_fill_into(plane, (row, col), connectivity, max_depth, passable)
+}
+
+/// `flood_fill_with`, collecting `reached` into whatever set `S` is
+pub fn flood_fill_into<T: Debug, S: Default + Extend<(usize, usize)>>(
+    plane: &[Vec<T>],
+    (row, col): (usize, usize),
+    connectivity: Connectivity,
+    max_depth: Option<usize>,
+    mut passable: impl FnMut(&T, &T) -> bool,
+) -> Flood<S> {
+    let mut distance = plane
+        .iter()
+        .map(|lat| vec![None; lat.len()])
+        .collect::<Grid<_>>();
+    distance[row][col] = Some(0);
+    let mut reached = S::default();
+    reached.extend([(row, col)]);
+
+    let mut queue = VecDeque::from([(row, col)]);
+    while let Some((row, col)) = queue.pop_front() {
+        let steps = distance[row][col].unwrap();
+        if max_depth.is_some_and(|max_depth| steps >= max_depth) {
+            continue;
+        }
+
+        for (next, next_row, next_col, _) in surrounding(plane, row, col, connectivity.directions())
+        {
+            if distance[next_row][next_col].is_none() && passable(&plane[row][col], next) {
+                distance[next_row][next_col] = Some(steps + 1);
+                reached.extend([(next_row, next_col)]);
+                queue.push_back((next_row, next_col));
+            }
+        }
+    }
+
+    Flood { distance, reached }
+}
+
+#[test]
+fn connectivity() {
+    let plane = vec![vec![1, 0, 1], vec![0, 1, 0], vec![1, 0, 1]];
+
+    let four = flood_fill_with(&plane, (1, 1), Connectivity::Four, None, |a, b| a == b);
+    assert_eq!(four.reached, FastSet::from_iter([(1, 1)]));
+
+    let eight = flood_fill_with(&plane, (1, 1), Connectivity::Eight, None, |a, b| a == b);
+    assert_eq!(eight.reached.len(), 5);
+    assert_eq!(eight.distance_to(0, 2), Some(1));
+    assert_eq!(eight.distance_to(0, 1), None);
+}
+
+#[test]
+fn uphill() {
+    let heights = vec![
+        vec![0, 1, 2, 3],
+        vec![1, 2, 5, 4],
+        vec![8, 7, 6, 5],
+        vec![9, 8, 7, 6],
+    ];
+    let climb = |from: &u32, to: &u32| *to == from + 1;
+
+    let trail = flood_fill_with(&heights, (0, 0), Connectivity::Four, None, climb);
+    assert_eq!(trail.distance_to(3, 0), Some(9));
+    assert_eq!(trail.distance_to(3, 1), Some(8));
+    assert_eq!(trail.reached.len(), 16);
+
+    let short = flood_fill_with(&heights, (0, 0), Connectivity::Four, Some(3), climb);
+    assert_eq!(
+        short
+            .reached
+            .iter()
+            .map(|&(row, col)| heights[row][col])
+            .max(),
+        Some(3)
+    );
+}
@@ -0,0 +1,195 @@
+//! grids where each cell is a whole grapheme cluster instead of a `char`, so accents and emoji
+//! tiles take one cell each
+
+use super::Grid;
+use std::{
+    cmp::Ordering,
+    fmt::{self, Debug, Display},
+    hash::{Hash, Hasher},
+    ops::Deref,
+};
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+/// longest grapheme kept without allocating. most emoji sequences fit
+const INLINE: usize = 22;
+
+#[derive(Clone)]
+enum Repr {
+    Inline { len: u8, bytes: [u8; INLINE] },
+    Boxed(Box<str>),
+}
+
+/// one grapheme cluster, like `char` but for what people think of as a character
+#[derive(Clone)]
+pub struct Glyph(Repr);
+
+impl Glyph {
+    pub fn new(text: &str) -> Self {
+        if text.len() <= INLINE {
+            let mut bytes = [0; INLINE];
+            bytes[..text.len()].copy_from_slice(text.as_bytes());
+            Glyph(Repr::Inline {
+                len: text.len() as u8,
+                bytes,
+            })
+        } else {
+            Glyph(Repr::Boxed(text.into()))
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        match &self.0 {
+            Repr::Inline { len, bytes } => {
+                std::str::from_utf8(&bytes[..*len as usize]).expect("glyphs are made from strs")
+            }
+            Repr::Boxed(text) => text,
+        }
+    }
+
+    /// columns it takes up in a terminal: 2 for east asian wide characters and emoji, 0 if it's
+    /// empty, otherwise 1
+    pub fn width(&self) -> usize {
+        self.as_str().width()
+    }
+}
+
+/// the grapheme clusters in a line, in order
+pub fn graphemes(line: &str) -> impl Iterator<Item = Glyph> + '_ {
+    line.graphemes(true).map(Glyph::new)
+}
+
+/// one line per row, with every cell padded out to the widest one in its column so mixing
+/// wide and narrow tiles doesn't knock the columns out of line
+pub fn render(grid: &[Vec<Glyph>]) -> String {
+    let mut widths = Vec::<usize>::new();
+    for row in grid {
+        for (col, glyph) in row.iter().enumerate() {
+            if col == widths.len() {
+                widths.push(0);
+            }
+            widths[col] = widths[col].max(glyph.width());
+        }
+    }
+
+    let mut text = String::new();
+    for row in grid {
+        for (glyph, &width) in row.iter().zip(&widths) {
+            text.push_str(glyph);
+            text.extend(std::iter::repeat_n(' ', width - glyph.width()));
+        }
+        text.push('\n');
+    }
+    text
+}
+
+/// `f` of every grapheme in each line
+pub fn grid_map<T>(text: &str, mut f: impl FnMut(&str) -> T) -> Grid<T> {
+    text.lines()
+        .map(|line| line.graphemes(true).map(&mut f).collect())
+        .collect()
+}
+
+impl Deref for Glyph {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        self.as_str()
+    }
+}
+
+impl From<&str> for Glyph {
+    fn from(text: &str) -> Self {
+        Glyph::new(text)
+    }
+}
+
+impl From<char> for Glyph {
+    fn from(c: char) -> Self {
+        Glyph::new(c.encode_utf8(&mut [0; 4]))
+    }
+}
+
+impl PartialEq for Glyph {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl Eq for Glyph {}
+
+impl PartialEq<str> for Glyph {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<&str> for Glyph {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl Hash for Glyph {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_str().hash(state)
+    }
+}
+
+impl PartialOrd for Glyph {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Glyph {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.as_str().cmp(other.as_str())
+    }
+}
+
+impl Debug for Glyph {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl Display for Glyph {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[test]
+fn clusters() {
+    let line = "e\u{301}#👩‍👩‍👧‍👦🌲.";
+    let glyphs = graphemes(line).collect::<Vec<_>>();
+    assert_eq!(glyphs, vec!["e\u{301}", "#", "👩‍👩‍👧‍👦", "🌲", "."]);
+    assert_eq!(
+        glyphs.iter().map(Glyph::width).collect::<Vec<_>>(),
+        vec![1, 1, 2, 2, 1]
+    );
+    // too long to keep inline, but still the same glyph
+    assert!(matches!(glyphs[2].0, Repr::Boxed(_)));
+    assert_eq!(glyphs[2], Glyph::new("👩‍👩‍👧‍👦"));
+    assert_eq!(Glyph::from('#'), glyphs[1]);
+    assert_eq!(glyphs.iter().map(Glyph::as_str).collect::<String>(), line);
+}
+
+#[test]
+fn rendering() {
+    let grid = grid_map("🌲.🌲\n#é#\n..", Glyph::new);
+    assert_eq!(grid[1].len(), 3);
+    assert_eq!(render(&grid), "🌲.🌲\n# é# \n. .\n");
+
+    // wide tiles that aren't in the big emoji blocks
+    let tiles = grid_map("⬛⬜\n.⭐\n✅⌚\n🇬🇧.", Glyph::new);
+    assert!(tiles
+        .iter()
+        .flatten()
+        .all(|glyph| glyph.width() == 2 || glyph == "."));
+    assert_eq!(render(&tiles), "⬛⬜\n. ⭐\n✅⌚\n🇬🇧. \n");
+
+    let plain = "#..\n.#.";
+    assert_eq!(render(&grid_map(plain, Glyph::new)), format!("{}\n", plain));
+}
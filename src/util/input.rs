@@ -0,0 +1,206 @@
+use super::{
+    grapheme::{self, Glyph},
+    scan::ScanError,
+    Grid,
+};
+use std::{ops::Deref, str::FromStr};
+
+/// puzzle input with line endings normalized to `\n`, whitespace-only lines made empty, and
+/// trailing blank lines removed. spaces on the end of a line are kept, since they can be part
+/// of a map. line numbers start at 1
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Input {
+    text: String,
+}
+
+/// one line of input and where it came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Line<'a> {
+    pub number: usize,
+    pub text: &'a str,
+}
+
+/// lines between blank lines. `text` doesn't include the final newline or any whitespace at the
+/// end, and its `lines` have it trimmed off too
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Paragraph<'a> {
+    pub first_line: usize,
+    pub text: &'a str,
+}
+
+impl Input {
+    pub fn new(raw: &str) -> Self {
+        let mut text = String::with_capacity(raw.len());
+        for line in raw.lines() {
+            // lines() already took care of \r\n
+            let line = line.strip_suffix('\r').unwrap_or(line);
+            if !line.trim().is_empty() {
+                text.push_str(line);
+            }
+            text.push('\n');
+        }
+
+        let end = text.trim_end_matches('\n').len();
+        text.truncate(end);
+
+        Input { text }
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn lines(&self) -> impl Iterator<Item = Line<'_>> {
+        self.text.lines().enumerate().map(|(i, text)| Line {
+            number: i + 1,
+            text,
+        })
+    }
+
+    pub fn paragraphs(&self) -> impl Iterator<Item = Paragraph<'_>> {
+        let mut lines = self.lines().peekable();
+        std::iter::from_fn(move || {
+            while lines.next_if(|line| line.is_empty()).is_some() {}
+            let first = lines.next()?;
+
+            let start = first.text.as_ptr() as usize - self.text.as_ptr() as usize;
+            let mut end = start + first.trim_end().len();
+            while let Some(line) = lines.next_if(|line| !line.is_empty()) {
+                end = line.text.as_ptr() as usize - self.text.as_ptr() as usize
+                    + line.trim_end().len();
+            }
+
+            Some(Paragraph {
+                first_line: first.number,
+                text: &self.text[start..end],
+            })
+        })
+    }
+
+    pub fn grid(&self) -> Grid<char> {
+        self.grid_map(|c| c)
+    }
+
+    pub fn grid_map<T>(&self, mut f: impl FnMut(char) -> T) -> Grid<T> {
+        self.lines()
+            .map(|line| line.chars().map(&mut f).collect())
+            .collect()
+    }
+
+    /// like `grid` but with a cell per grapheme cluster, for maps with accents or emoji in
+    pub fn grapheme_grid(&self) -> Grid<Glyph> {
+        grapheme::grid_map(&self.text, Glyph::new)
+    }
+
+    /// whitespace-separated numbers on each line, so spaces on the end don't matter
+    pub fn numbers<T: FromStr>(&self) -> Result<Grid<T>, ScanError> {
+        self.lines().map(|line| line.numbers()).collect()
+    }
+}
+
+impl From<&str> for Input {
+    fn from(raw: &str) -> Self {
+        Input::new(raw)
+    }
+}
+
+impl<'a> Line<'a> {
+    pub fn graphemes(&self) -> impl Iterator<Item = Glyph> + 'a {
+        grapheme::graphemes(self.text)
+    }
+
+    pub fn numbers<T: FromStr>(&self) -> Result<Vec<T>, ScanError> {
+        self.text
+            .split_whitespace()
+            .map(|word| {
+                word.parse().map_err(|_| ScanError {
+                    line: self.number,
+                    column: self.text[..word.as_ptr() as usize - self.text.as_ptr() as usize]
+                        .chars()
+                        .count()
+                        + 1,
+                    message: format!("not a number: {:?}", word),
+                })
+            })
+            .collect()
+    }
+}
+
+impl Deref for Line<'_> {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        self.text
+    }
+}
+
+impl<'a> Paragraph<'a> {
+    pub fn lines(&self) -> impl Iterator<Item = Line<'a>> {
+        let first_line = self.first_line;
+        self.text.lines().enumerate().map(move |(i, text)| Line {
+            number: first_line + i,
+            text: text.trim_end(),
+        })
+    }
+}
+
+impl Deref for Paragraph<'_> {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        self.text
+    }
+}
+
+#[test]
+fn normalize() {
+    let input = Input::new("ab\r\ncd \r\n  \r\n\r\nef\n\n\n");
+    assert_eq!(input.text(), "ab\ncd \n\n\nef");
+    assert_eq!(input, Input::new("ab\ncd \n\n\nef"));
+    assert_eq!(input.grid()[1], vec!['c', 'd', ' ']);
+
+    let map = Input::new("🌲#\r\nx\u{301}.\r\n");
+    assert_eq!(map.grapheme_grid()[1], vec!["x\u{301}", "."]);
+    assert_eq!(map.lines().next().unwrap().graphemes().count(), 2);
+}
+
+#[test]
+fn paragraphs() {
+    let input = Input::from("1|2\r\n3|4\r\n \r\n5,6\r\n\r\n\r\n7\r\n");
+    let paragraphs = input.paragraphs().collect::<Vec<_>>();
+    assert_eq!(paragraphs.len(), 3);
+    assert_eq!(
+        (paragraphs[0].first_line, paragraphs[0].text),
+        (1, "1|2\n3|4")
+    );
+    assert_eq!((paragraphs[1].first_line, paragraphs[1].text), (4, "5,6"));
+    assert_eq!(paragraphs[2].lines().next().unwrap().number, 7);
+
+    // editors that leave spaces on the end, on windows
+    let input = Input::new("47|53 \r\n97|13\t\r\n \r\n75,47,61 \r\n");
+    let paragraphs = input.paragraphs().collect::<Vec<_>>();
+    assert_eq!(paragraphs[0].text, "47|53 \n97|13");
+    assert_eq!(
+        paragraphs[0]
+            .lines()
+            .map(|line| line.text)
+            .collect::<Vec<_>>(),
+        vec!["47|53", "97|13"]
+    );
+    assert_eq!(
+        (paragraphs[1].first_line, paragraphs[1].text),
+        (4, "75,47,61")
+    );
+}
+
+#[test]
+fn numbers() {
+    let input = Input::new("1 2 3 \r\n4  5 6\t\n");
+    assert_eq!(
+        input.numbers::<u8>(),
+        Ok(vec![vec![1, 2, 3], vec![4, 5, 6]])
+    );
+
+    let err = Input::new("1 2\n3 x4").numbers::<u8>().unwrap_err();
+    assert_eq!(err.to_string(), "2:3: not a number: \"x4\"");
+}
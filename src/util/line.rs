@@ -0,0 +1,153 @@
+//! straight lines on the integer grid. segments between two points use signed `(row, col)` so
+//! they can go off the edge of a plane, rays stay on a plane and use its indices
+
+use num::Integer;
+
+/// the smallest step in the same direction as `(row, col)` that still only lands on whole
+/// cells, so walking it hits every lattice point on the line. `(0, 0)` stays put
+pub fn reduce((row, col): (isize, isize)) -> (isize, isize) {
+    let gcd = row.gcd(&col);
+    if gcd == 0 {
+        (0, 0)
+    } else {
+        (row / gcd, col / gcd)
+    }
+}
+
+/// every cell exactly on the segment, both ends included
+pub fn lattice(from: (isize, isize), to: (isize, isize)) -> impl Iterator<Item = (isize, isize)> {
+    let (row_step, col_step) = reduce((to.0 - from.0, to.1 - from.1));
+    let steps = if row_step != 0 {
+        (to.0 - from.0) / row_step
+    } else if col_step != 0 {
+        (to.1 - from.1) / col_step
+    } else {
+        0
+    };
+    (0..=steps).map(move |i| (from.0 + i * row_step, from.1 + i * col_step))
+}
+
+/// the cells a drawn line goes through, one per step along whichever way it goes further,
+/// both ends included. consecutive cells always touch, possibly only diagonally
+#[derive(Debug, Clone)]
+pub struct Bresenham {
+    at: (isize, isize),
+    to: (isize, isize),
+    step: (isize, isize),
+    /// how far it goes in rows, negated, and in cols
+    delta: (isize, isize),
+    error: isize,
+    done: bool,
+}
+
+pub fn bresenham(from: (isize, isize), to: (isize, isize)) -> Bresenham {
+    let delta = (-(to.0 - from.0).abs(), (to.1 - from.1).abs());
+    Bresenham {
+        at: from,
+        to,
+        step: ((to.0 - from.0).signum(), (to.1 - from.1).signum()),
+        delta,
+        error: delta.0 + delta.1,
+        done: false,
+    }
+}
+
+impl Iterator for Bresenham {
+    type Item = (isize, isize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let at = self.at;
+        if at == self.to {
+            self.done = true;
+            return Some(at);
+        }
+
+        let twice = 2 * self.error;
+        if twice >= self.delta.0 {
+            self.error += self.delta.0;
+            self.at.1 += self.step.1;
+        }
+        if twice <= self.delta.1 {
+            self.error += self.delta.1;
+            self.at.0 += self.step.0;
+        }
+        Some(at)
+    }
+}
+
+/// cells of `plane` from `(row, col)` going `step` at a time until it falls off the edge. the
+/// start isn't included
+pub fn ray<T>(
+    plane: &[Vec<T>],
+    (row, col): (usize, usize),
+    step: (isize, isize),
+) -> impl Iterator<Item = (usize, usize)> + '_ {
+    assert!(
+        step != (0, 0),
+        "ray from {},{} doesn't go anywhere",
+        row,
+        col
+    );
+    std::iter::successors(Some((row, col)), move |&(row, col)| {
+        let row = row.checked_add_signed(step.0)?;
+        let col = col.checked_add_signed(step.1)?;
+        plane.get(row)?.get(col)?;
+        Some((row, col))
+    })
+    .skip(1)
+}
+
+/// the first cell along `ray` that `hit` likes, None if it gets to the edge without one
+pub fn cast<T>(
+    plane: &[Vec<T>],
+    start: (usize, usize),
+    step: (isize, isize),
+    mut hit: impl FnMut(&T) -> bool,
+) -> Option<(usize, usize)> {
+    ray(plane, start, step).find(|&(row, col)| hit(&plane[row][col]))
+}
+
+#[test]
+fn segments() {
+    assert_eq!(
+        lattice((0, 0), (4, -6)).collect::<Vec<_>>(),
+        vec![(0, 0), (2, -3), (4, -6)]
+    );
+    assert_eq!(lattice((3, 1), (3, 1)).collect::<Vec<_>>(), vec![(3, 1)]);
+    assert_eq!(lattice((0, 0), (5, 3)).count(), 2);
+    assert_eq!(reduce((0, -7)), (0, -1));
+
+    let drawn = bresenham((0, 0), (2, 5)).collect::<Vec<_>>();
+    assert_eq!(drawn, vec![(0, 0), (0, 1), (1, 2), (1, 3), (2, 4), (2, 5)]);
+    for (from, to) in [((4, 4), (0, 1)), ((0, 0), (-3, 3)), ((2, 2), (2, -2))] {
+        let drawn = bresenham(from, to).collect::<Vec<_>>();
+        assert_eq!((drawn[0], *drawn.last().unwrap()), (from, to));
+        assert_eq!(
+            drawn.len() as isize,
+            (to.0 - from.0).abs().max((to.1 - from.1).abs()) + 1
+        );
+        assert!(drawn
+            .windows(2)
+            .all(|pair| (pair[0].0 - pair[1].0).abs() <= 1 && (pair[0].1 - pair[1].1).abs() <= 1));
+    }
+}
+
+#[test]
+fn rays() {
+    let plane = vec![
+        vec!['.', '.', '.', '#'],
+        vec!['.', '.', '.', '.'],
+        vec!['#', '.', '.', '.'],
+    ];
+    assert_eq!(
+        ray(&plane, (2, 0), (-1, 1)).collect::<Vec<_>>(),
+        vec![(1, 1), (0, 2)]
+    );
+    assert_eq!(ray(&plane, (0, 0), (0, -1)).count(), 0);
+    assert_eq!(cast(&plane, (0, 0), (0, 1), |&c| c == '#'), Some((0, 3)));
+    assert_eq!(cast(&plane, (0, 0), (1, 1), |&c| c == '#'), None);
+}
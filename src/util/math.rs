@@ -0,0 +1,235 @@
+use num::{rational::Ratio, Integer, Signed, Zero};
+
+/// `gcd` along with x and y such that `a * x + b * y == gcd`
+pub fn extended_gcd<T: Integer + Signed + Copy>(a: T, b: T) -> (T, T, T) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (T::one(), T::zero());
+    let (mut old_y, mut y) = (T::zero(), T::one());
+
+    while !r.is_zero() {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        (old_x, x) = (x, old_x - q * x);
+        (old_y, y) = (y, old_y - q * y);
+    }
+
+    if old_r.is_negative() {
+        (-old_r, -old_x, -old_y)
+    } else {
+        (old_r, old_x, old_y)
+    }
+}
+
+/// every solution to `a * x + b * y == c`: `(x + k * step_x, y + k * step_y)` for any integer k
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Diophantine<T> {
+    pub x: T,
+    pub y: T,
+    pub step_x: T,
+    pub step_y: T,
+}
+
+impl<T: Integer + Copy> Diophantine<T> {
+    pub fn at(&self, k: T) -> (T, T) {
+        (self.x + k * self.step_x, self.y + k * self.step_y)
+    }
+}
+
+/// solve `a * x + b * y == c` over the integers. None if there are no solutions, or if a and b
+/// are both zero and so x and y can be anything
+pub fn diophantine<T: Integer + Signed + Copy>(a: T, b: T, c: T) -> Option<Diophantine<T>> {
+    if a.is_zero() && b.is_zero() {
+        return None;
+    }
+
+    let (g, x, y) = extended_gcd(a, b);
+    if !(c % g).is_zero() {
+        return None;
+    }
+
+    let scale = c / g;
+    Some(Diophantine {
+        x: x * scale,
+        y: y * scale,
+        step_x: b / g,
+        step_y: -(a / g),
+    })
+}
+
+/// cramer's rule for `[[a, b], [c, d]] * [x, y] == [e, f]`. None if the determinant is zero
+pub fn solve_2x2<T: Integer + Signed + Copy>(
+    matrix: [[T; 2]; 2],
+    rhs: [T; 2],
+) -> Option<[Ratio<T>; 2]> {
+    let [[a, b], [c, d]] = matrix;
+    let [e, f] = rhs;
+
+    let det = a * d - b * c;
+    if det.is_zero() {
+        return None;
+    }
+
+    Some([
+        Ratio::new(e * d - b * f, det),
+        Ratio::new(a * f - e * c, det),
+    ])
+}
+
+/// like `solve_2x2`, but None unless x and y are both integers
+pub fn solve_2x2_integer<T: Integer + Signed + Copy>(
+    matrix: [[T; 2]; 2],
+    rhs: [T; 2],
+) -> Option<[T; 2]> {
+    let [x, y] = solve_2x2(matrix, rhs)?;
+    (x.is_integer() && y.is_integer()).then(|| [x.to_integer(), y.to_integer()])
+}
+
+/// bareiss' fraction-free elimination, so every intermediate value stays an integer
+pub fn determinant<T: Integer + Signed + Copy>(matrix: &[Vec<T>]) -> T {
+    let n = matrix.len();
+    let mut m = matrix.to_vec();
+    let mut sign = T::one();
+    let mut prev_pivot = T::one();
+
+    for k in 0..n {
+        if m[k][k].is_zero() {
+            let Some(swap) = (k + 1..n).find(|&row| !m[row][k].is_zero()) else {
+                return T::zero();
+            };
+            m.swap(k, swap);
+            sign = -sign;
+        }
+
+        for i in k + 1..n {
+            for j in k + 1..n {
+                m[i][j] = (m[i][j] * m[k][k] - m[i][k] * m[k][j]) / prev_pivot;
+            }
+        }
+        prev_pivot = m[k][k];
+    }
+
+    if n == 0 {
+        T::one()
+    } else {
+        sign * m[n - 1][n - 1]
+    }
+}
+
+/// gaussian elimination over exact fractions. None unless there's exactly one solution
+pub fn solve<T: Integer + Signed + Copy>(matrix: &[Vec<T>], rhs: &[T]) -> Option<Vec<Ratio<T>>> {
+    let n = matrix.len();
+    assert_eq!(
+        n,
+        rhs.len(),
+        "matrix and rhs have different numbers of rows"
+    );
+
+    let mut m = matrix
+        .iter()
+        .zip(rhs)
+        .map(|(row, b)| {
+            assert_eq!(n, row.len(), "matrix isn't square");
+            row.iter()
+                .chain(std::iter::once(b))
+                .map(|&value| Ratio::from_integer(value))
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    for col in 0..n {
+        let pivot = (col..n).find(|&row| !m[row][col].is_zero())?;
+        m.swap(col, pivot);
+
+        let pivot_row = m[col].clone();
+        for (row, r) in m.iter_mut().enumerate() {
+            if row == col || r[col].is_zero() {
+                continue;
+            }
+            let factor = r[col] / pivot_row[col];
+            for (value, pivot_value) in r.iter_mut().zip(pivot_row.iter()).skip(col) {
+                *value = *value - factor * *pivot_value;
+            }
+        }
+    }
+
+    Some((0..n).map(|row| m[row][n] / m[row][row]).collect())
+}
+
+/// like `solve`, but None unless every variable is an integer
+pub fn solve_integer<T: Integer + Signed + Copy>(matrix: &[Vec<T>], rhs: &[T]) -> Option<Vec<T>> {
+    solve(matrix, rhs)?
+        .into_iter()
+        .map(|x| x.is_integer().then(|| x.to_integer()))
+        .collect()
+}
+
+/// chinese remainder theorem for `x == remainder (mod modulus)` pairs. moduli don't need to be
+/// coprime. gives the smallest non-negative x along with the combined modulus, or None if the
+/// congruences contradict each other
+pub fn crt<T: Integer + Signed + Copy>(congruences: &[(T, T)]) -> Option<(T, T)> {
+    let mut x = T::zero();
+    let mut modulus = T::one();
+
+    for &(remainder, m) in congruences {
+        let (g, p, _) = extended_gcd(modulus, m);
+        let diff = remainder - x;
+        if !(diff % g).is_zero() {
+            return None;
+        }
+
+        let lcm = modulus / g * m;
+        let k = (diff / g * p).mod_floor(&(m / g));
+        x = (x + modulus * k).mod_floor(&lcm);
+        modulus = lcm;
+    }
+
+    Some((x, modulus))
+}
+
+#[test]
+fn gcd() {
+    for (a, b) in [(240i64, 46), (-240, 46), (17, 0), (0, -5), (12, -18)] {
+        let (g, x, y) = extended_gcd(a, b);
+        assert_eq!(g, a.gcd(&b));
+        assert_eq!(a * x + b * y, g);
+    }
+}
+
+#[test]
+fn linear_diophantine() {
+    let solutions = diophantine(6i64, 15, 21).unwrap();
+    for k in -5..5 {
+        let (x, y) = solutions.at(k);
+        assert_eq!(6 * x + 15 * y, 21);
+    }
+    assert_eq!(diophantine(6i64, 15, 22), None);
+}
+
+#[test]
+fn systems() {
+    // button a: x+94 y+34, button b: x+22 y+67, prize at 8400,5400
+    assert_eq!(
+        solve_2x2_integer([[94i64, 22], [34, 67]], [8400, 5400]),
+        Some([80, 40])
+    );
+    assert_eq!(
+        solve_2x2_integer([[26i64, 67], [66, 21]], [12748, 12176]),
+        None
+    );
+    assert_eq!(solve_2x2([[1i64, 2], [2, 4]], [1, 2]), None);
+
+    let matrix = vec![vec![2i64, 1, -1], vec![-3, -1, 2], vec![-2, 1, 2]];
+    assert_eq!(solve_integer(&matrix, &[8, -11, -3]), Some(vec![2, 3, -1]));
+    assert_eq!(determinant(&matrix), -1);
+    assert_eq!(
+        solve(&[vec![2i64, 0], vec![0, 3]], &[1, 1]),
+        Some(vec![Ratio::new(1, 2), Ratio::new(1, 3)])
+    );
+}
+
+#[test]
+fn remainders() {
+    assert_eq!(crt(&[(2i64, 3), (3, 5), (2, 7)]), Some((23, 105)));
+    assert_eq!(crt(&[(1i64, 4), (3, 6)]), Some((9, 12)));
+    assert_eq!(crt(&[(1i64, 4), (2, 6)]), None);
+}
@@ -0,0 +1,111 @@
+use std::{
+    collections::{hash_map::RandomState, HashMap},
+    hash::{BuildHasher, Hash},
+};
+
+/// the recursive call handed to a memoized function
+pub type Recurse<'a, K, V> = &'a mut dyn FnMut(K) -> V;
+
+/// a `Memo` hashed like `FastMap`
+pub type FastMemo<K, V> = Memo<K, V, fnv::FnvBuildHasher>;
+
+/// cache for recursive functions. the function gets a `Recurse` to call instead of itself, so
+/// nobody has to pass the cache around by hand. pick the hasher with `H`
+#[derive(Debug, Clone)]
+pub struct Memo<K, V, H = RandomState> {
+    cache: HashMap<K, V, H>,
+}
+
+impl<K, V, H: Default> Default for Memo<K, V, H> {
+    fn default() -> Self {
+        Memo {
+            cache: HashMap::default(),
+        }
+    }
+}
+
+impl<K: Clone + Eq + Hash, V: Clone> Memo<K, V> {
+    pub fn new() -> Self {
+        Memo::default()
+    }
+}
+
+impl<K: Clone + Eq + Hash, V: Clone, H: BuildHasher> Memo<K, V, H> {
+    pub fn with_hasher(hasher: H) -> Self {
+        Memo {
+            cache: HashMap::with_hasher(hasher),
+        }
+    }
+
+    pub fn get(&self, key: &K) -> Option<&V> {
+        self.cache.get(key)
+    }
+
+    pub fn len(&self) -> usize {
+        self.cache.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cache.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.cache.clear();
+    }
+
+    /// the cached value for `key`, or `f` of it. `f` should be the same function every time for
+    /// the same cache, since it doesn't get called again for keys it has already seen
+    pub fn get_or_compute(&mut self, key: K, f: impl Fn(Recurse<'_, K, V>, &K) -> V) -> V {
+        fn go<K: Clone + Eq + Hash, V: Clone, H: BuildHasher>(
+            cache: &mut HashMap<K, V, H>,
+            f: &dyn Fn(Recurse<'_, K, V>, &K) -> V,
+            key: K,
+        ) -> V {
+            if let Some(value) = cache.get(&key) {
+                return value.clone();
+            }
+            let value = f(&mut |key| go(cache, f, key), &key);
+            cache.insert(key, value.clone());
+            value
+        }
+
+        go(&mut self.cache, &f, key)
+    }
+}
+
+#[test]
+fn fibonacci() {
+    let fib = |fib: Recurse<u64, u64>, &n: &u64| if n < 2 { n } else { fib(n - 1) + fib(n - 2) };
+
+    let mut memo = Memo::new();
+    assert_eq!(memo.get_or_compute(90, fib), 2880067194370816120);
+    assert_eq!(memo.len(), 91);
+    assert_eq!(memo.get(&50), Some(&12586269025));
+}
+
+#[test]
+fn hasher() {
+    #[derive(Default)]
+    struct Identity(u64);
+
+    impl std::hash::Hasher for Identity {
+        fn finish(&self) -> u64 {
+            self.0
+        }
+
+        fn write(&mut self, bytes: &[u8]) {
+            for byte in bytes {
+                self.0 = (self.0 << 8) | *byte as u64;
+            }
+        }
+    }
+
+    let mut memo = Memo::<u64, u64, std::hash::BuildHasherDefault<Identity>>::default();
+    let collatz = |steps: Recurse<u64, u64>, &n: &u64| match n {
+        1 => 0,
+        n if n.is_multiple_of(2) => 1 + steps(n / 2),
+        n => 1 + steps(3 * n + 1),
+    };
+    assert_eq!(memo.get_or_compute(27, collatz), 111);
+    assert_eq!(memo.get_or_compute(9, collatz), 19);
+}
@@ -0,0 +1,203 @@
+//! positions and dense grids in any number of dimensions. neighbours come in two kinds: one
+//! step along an axis (2 * D of them) or diagonals too (3^D - 1)
+
+/// a position in D dimensions, most significant axis first like `(row, col)`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct PosN<const D: usize>(pub [isize; D]);
+
+/// one step along each axis, both ways, in order of axis
+pub fn axis_offsets<const D: usize>() -> impl Iterator<Item = [isize; D]> {
+    (0..D).flat_map(|axis| {
+        [-1, 1].map(|step| {
+            let mut offset = [0; D];
+            offset[axis] = step;
+            offset
+        })
+    })
+}
+
+/// every offset with each coordinate in -1..=1 except all zeroes, in sorted order. for D = 2
+/// that's the same order as `Direction::OFFSETS`
+pub fn all_offsets<const D: usize>() -> impl Iterator<Item = [isize; D]> {
+    (0..3usize.pow(D as u32))
+        .map(|mut n| {
+            let mut offset = [0; D];
+            for coord in offset.iter_mut().rev() {
+                *coord = (n % 3) as isize - 1;
+                n /= 3;
+            }
+            offset
+        })
+        .filter(|offset| offset.iter().any(|&coord| coord != 0))
+}
+
+impl<const D: usize> PosN<D> {
+    pub const ORIGIN: PosN<D> = PosN([0; D]);
+
+    pub fn offset(&self, by: [isize; D]) -> Self {
+        PosN(std::array::from_fn(|axis| self.0[axis] + by[axis]))
+    }
+
+    /// one step along each axis, both ways, see `axis_offsets`
+    pub fn axis_neighbours(&self) -> impl Iterator<Item = PosN<D>> {
+        let pos = *self;
+        axis_offsets().map(move |by| pos.offset(by))
+    }
+
+    /// diagonals too, see `all_offsets`
+    pub fn all_neighbours(&self) -> impl Iterator<Item = PosN<D>> {
+        let pos = *self;
+        all_offsets().map(move |by| pos.offset(by))
+    }
+
+    pub fn manhattan(&self, other: &PosN<D>) -> usize {
+        (0..D)
+            .map(|axis| self.0[axis].abs_diff(other.0[axis]))
+            .sum()
+    }
+}
+
+/// a dense box of cells from the origin up to `dims`, stored with the last axis varying fastest
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GridN<T, const D: usize> {
+    dims: [usize; D],
+    cells: Vec<T>,
+}
+
+impl<T, const D: usize> GridN<T, D> {
+    pub fn new(dims: [usize; D], fill: T) -> Self
+    where
+        T: Clone,
+    {
+        GridN {
+            dims,
+            cells: vec![fill; dims.iter().product()],
+        }
+    }
+
+    pub fn dims(&self) -> [usize; D] {
+        self.dims
+    }
+
+    /// number of cells
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// where `pos` is in the flat storage, None if it's outside
+    fn index(&self, pos: PosN<D>) -> Option<usize> {
+        let mut index = 0;
+        for (&coord, &dim) in pos.0.iter().zip(&self.dims) {
+            let coord = usize::try_from(coord).ok().filter(|&coord| coord < dim)?;
+            index = index * dim + coord;
+        }
+        Some(index)
+    }
+
+    fn position(&self, mut index: usize) -> PosN<D> {
+        let mut pos = [0; D];
+        for (coord, &dim) in pos.iter_mut().zip(&self.dims).rev() {
+            *coord = (index % dim) as isize;
+            index /= dim;
+        }
+        PosN(pos)
+    }
+
+    pub fn contains(&self, pos: PosN<D>) -> bool {
+        self.index(pos).is_some()
+    }
+
+    pub fn get(&self, pos: PosN<D>) -> Option<&T> {
+        Some(&self.cells[self.index(pos)?])
+    }
+
+    pub fn get_mut(&mut self, pos: PosN<D>) -> Option<&mut T> {
+        let index = self.index(pos)?;
+        Some(&mut self.cells[index])
+    }
+
+    /// the old value. panics if `pos` is outside
+    pub fn set(&mut self, pos: PosN<D>, value: T) -> T {
+        let Some(index) = self.index(pos) else {
+            panic!("{:?} is outside {:?}", pos, self.dims);
+        };
+        std::mem::replace(&mut self.cells[index], value)
+    }
+
+    /// every position with its value, last axis varying fastest
+    pub fn iter(&self) -> impl Iterator<Item = (PosN<D>, &T)> + '_ {
+        self.cells
+            .iter()
+            .enumerate()
+            .map(|(index, value)| (self.position(index), value))
+    }
+
+    /// `axis_neighbours` that are inside the grid, like `surrounding_cardinal` in 2D
+    pub fn surrounding_axes(&self, pos: PosN<D>) -> impl Iterator<Item = (&T, PosN<D>)> + '_ {
+        pos.axis_neighbours()
+            .filter_map(|next| Some((self.get(next)?, next)))
+    }
+
+    /// `all_neighbours` that are inside the grid, like `surrounding_all` in 2D
+    pub fn surrounding_all(&self, pos: PosN<D>) -> impl Iterator<Item = (&T, PosN<D>)> + '_ {
+        pos.all_neighbours()
+            .filter_map(|next| Some((self.get(next)?, next)))
+    }
+}
+
+impl<T: Clone> GridN<T, 2> {
+    /// assumed to be rectangular, going by the first row
+    pub fn from_plane(plane: &[Vec<T>]) -> Self {
+        let width = plane.first().map_or(0, Vec::len);
+        GridN {
+            dims: [plane.len(), width],
+            cells: plane
+                .iter()
+                .flat_map(|row| row[..width].iter().cloned())
+                .collect(),
+        }
+    }
+}
+
+#[test]
+fn offsets() {
+    use super::Direction;
+
+    assert_eq!(all_offsets::<2>().collect::<Vec<_>>(), {
+        Direction::OFFSETS.map(|(row, col)| [row, col]).to_vec()
+    });
+    for (d, axis, all) in [(1, 2, 2), (3, 6, 26), (4, 8, 80)] {
+        let counts = match d {
+            1 => (axis_offsets::<1>().count(), all_offsets::<1>().count()),
+            3 => (axis_offsets::<3>().count(), all_offsets::<3>().count()),
+            _ => (axis_offsets::<4>().count(), all_offsets::<4>().count()),
+        };
+        assert_eq!(counts, (axis, all), "{} dimensions", d);
+    }
+
+    let pos = PosN([1, -2, 3]);
+    assert!(pos.axis_neighbours().all(|next| next.manhattan(&pos) == 1));
+    assert_eq!(pos.axis_neighbours().count(), 6);
+    assert_eq!(pos.all_neighbours().count(), 26);
+    assert_eq!(PosN::ORIGIN.manhattan(&pos), 6);
+}
+
+#[test]
+fn grid() {
+    let mut cube = GridN::new([2, 3, 4], 0);
+    assert_eq!(cube.len(), 24);
+    cube.set(PosN([1, 2, 3]), 7);
+    assert_eq!(cube.get(PosN([1, 2, 3])), Some(&7));
+    assert_eq!(cube.get(PosN([2, 0, 0])), None);
+    assert_eq!(cube.get(PosN([0, -1, 0])), None);
+    assert_eq!(cube.iter().last(), Some((PosN([1, 2, 3]), &7)));
+    assert_eq!(cube.surrounding_all(PosN([0, 0, 0])).count(), 7);
+    assert_eq!(cube.surrounding_axes(PosN([0, 1, 1])).count(), 5);
+
+    let flat = GridN::from_plane(&[vec!['a', 'b'], vec!['c', 'd']]);
+    assert_eq!(flat.get(PosN([1, 0])), Some(&'c'));
+}
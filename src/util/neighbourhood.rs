@@ -0,0 +1,188 @@
+//! which of a cell's eight neighbours are "in", packed into a `u8` so anything about the 3x3
+//! block around a cell can be looked up in a 256 entry table
+
+use super::{surrounding_all, transform::Transform, Direction};
+use std::fmt::Debug;
+
+/// bit `direction.index()` is set when the neighbour that way is in
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Neighbourhood(pub u8);
+
+/// where each bit sits in the 3x3 block, in `Direction::ALL` order
+const CELLS: [(usize, usize); 8] = [
+    (0, 0),
+    (0, 1),
+    (0, 2),
+    (1, 0),
+    (1, 2),
+    (2, 0),
+    (2, 1),
+    (2, 2),
+];
+
+const NW: u8 = 1 << Direction::NW as u8;
+const N: u8 = 1 << Direction::N as u8;
+const NE: u8 = 1 << Direction::NE as u8;
+const W: u8 = 1 << Direction::W as u8;
+const E: u8 = 1 << Direction::E as u8;
+const SW: u8 = 1 << Direction::SW as u8;
+const S: u8 = 1 << Direction::S as u8;
+const SE: u8 = 1 << Direction::SE as u8;
+
+impl Neighbourhood {
+    pub const EMPTY: Neighbourhood = Neighbourhood(0);
+    pub const FULL: Neighbourhood = Neighbourhood(u8::MAX);
+
+    /// `corners` of every neighbourhood
+    pub const CORNERS: [u8; 256] = {
+        let mut table = [0; 256];
+        let mut mask = 0;
+        while mask < 256 {
+            table[mask] = Neighbourhood(mask as u8).corners();
+            mask += 1;
+        }
+        table
+    };
+
+    /// `edges` of every neighbourhood
+    pub const EDGES: [u8; 256] = {
+        let mut table = [0; 256];
+        let mut mask = 0;
+        while mask < 256 {
+            table[mask] = Neighbourhood(mask as u8).edges();
+            mask += 1;
+        }
+        table
+    };
+
+    pub fn from_fn(mut inside: impl FnMut(Direction) -> bool) -> Self {
+        Direction::ALL
+            .into_iter()
+            .filter(|&direction| inside(direction))
+            .fold(Neighbourhood::EMPTY, Neighbourhood::with)
+    }
+
+    /// the neighbours of `row`,`col` that `inside` likes. off the edge of the plane is never in
+    pub fn of<T: Debug>(
+        plane: &[Vec<T>],
+        row: usize,
+        col: usize,
+        mut inside: impl FnMut(&T) -> bool,
+    ) -> Self {
+        surrounding_all(plane, row, col)
+            .filter(|(value, ..)| inside(value))
+            .fold(Neighbourhood::EMPTY, |neighbourhood, (.., direction)| {
+                neighbourhood.with(direction)
+            })
+    }
+
+    pub fn contains(&self, direction: Direction) -> bool {
+        self.0 & (1 << direction.index()) != 0
+    }
+
+    pub fn with(self, direction: Direction) -> Self {
+        Neighbourhood(self.0 | 1 << direction.index())
+    }
+
+    pub fn without(self, direction: Direction) -> Self {
+        Neighbourhood(self.0 & !(1 << direction.index()))
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// corners of the middle cell when it's in too. a corner is outside if neither neighbour
+    /// next to it is in, and inside if both are but the diagonal one isn't
+    pub const fn corners(self) -> u8 {
+        let mask = self.0;
+        let mut corners = 0;
+        let around = [(N, W, NW), (N, E, NE), (S, W, SW), (S, E, SE)];
+        let mut i = 0;
+        while i < around.len() {
+            let (vertical, horizontal, diagonal) = around[i];
+            let vertical = mask & vertical != 0;
+            let horizontal = mask & horizontal != 0;
+            let diagonal = mask & diagonal != 0;
+            if (!vertical && !horizontal) || (vertical && horizontal && !diagonal) {
+                corners += 1;
+            }
+            i += 1;
+        }
+        corners
+    }
+
+    /// sides of the middle cell with nothing on the other side
+    pub const fn edges(self) -> u8 {
+        4 - (self.0 & (N | W | E | S)).count_ones() as u8
+    }
+
+    /// the neighbourhood of the same cell after turning or flipping the whole grid
+    pub fn transformed(self, transform: Transform) -> Self {
+        let mut block = vec![vec![false; 3]; 3];
+        for (i, &(row, col)) in CELLS.iter().enumerate() {
+            block[row][col] = self.0 & (1 << i) != 0;
+        }
+        let block = transform.apply(&block);
+        Neighbourhood::from_fn(|direction| {
+            let (row, col) = CELLS[direction.index()];
+            block[row][col]
+        })
+    }
+
+    /// `f` of every possible neighbourhood, indexed by the mask
+    pub fn table<T>(mut f: impl FnMut(Neighbourhood) -> T) -> [T; 256] {
+        std::array::from_fn(|mask| f(Neighbourhood(mask as u8)))
+    }
+}
+
+#[test]
+fn transforms() {
+    let hook = Neighbourhood::EMPTY.with(Direction::N).with(Direction::NE);
+    assert_eq!(
+        hook.transformed(Transform::Rotate90),
+        Neighbourhood::EMPTY.with(Direction::E).with(Direction::SE)
+    );
+    assert_eq!(
+        hook.transformed(Transform::FlipHorizontal),
+        Neighbourhood::EMPTY.with(Direction::N).with(Direction::NW)
+    );
+    assert_eq!(hook.transformed(Transform::Identity), hook);
+
+    // turning things around doesn't change how many corners or edges there are
+    for neighbourhood in Neighbourhood::table(|neighbourhood| neighbourhood) {
+        for transform in Transform::ALL {
+            let turned = neighbourhood.transformed(transform);
+            assert_eq!(turned.len(), neighbourhood.len());
+            assert_eq!(turned.corners(), neighbourhood.corners());
+            assert_eq!(turned.edges(), neighbourhood.edges());
+        }
+    }
+}
+
+#[test]
+fn classification() {
+    let corners = |mask: u8| Neighbourhood::CORNERS[mask as usize];
+    assert_eq!(corners(0), 4);
+    assert_eq!(Neighbourhood::EDGES[0], 4);
+    assert_eq!(corners(u8::MAX), 0);
+    assert_eq!(Neighbourhood::EDGES[u8::MAX as usize], 0);
+    // the arms of a plus sign, with nothing in the diagonals
+    assert_eq!(corners(N | S | E | W), 4);
+    // only diagonals, which a lone cell doesn't care about
+    assert_eq!(corners(NW | NE | SW | SE), 4);
+    assert_eq!(corners(N | W | NW), 1);
+    assert_eq!(corners(N | S), 0);
+
+    let plane = vec![vec![1, 1, 0], vec![0, 1, 1], vec![1, 0, 1]];
+    let middle = Neighbourhood::of(&plane, 1, 1, |&value| value == 1);
+    assert_eq!(middle, Neighbourhood(NW | N | E | SW | SE));
+    assert_eq!(
+        Neighbourhood::of(&plane, 0, 0, |&value| value == 1).len(),
+        2
+    );
+}
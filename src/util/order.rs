@@ -0,0 +1,301 @@
+use std::{
+    cmp::Ordering,
+    collections::{BTreeMap, BTreeSet},
+};
+
+/// a set of "this comes before that" rules. btree-backed so sorts come out the same every run:
+/// when several nodes could go next, the smallest one does
+#[derive(Debug, Clone)]
+pub struct Order<T> {
+    /// things that must come after a T
+    nexts: BTreeMap<T, BTreeSet<T>>,
+
+    /// things that must come before a T
+    prevs: BTreeMap<T, BTreeSet<T>>,
+}
+
+/// nodes that form a cycle, in order. the last node comes before the first
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cycle<T>(pub Vec<T>);
+
+impl<T: Clone + Ord> Default for Order<T> {
+    fn default() -> Self {
+        Order {
+            nexts: BTreeMap::new(),
+            prevs: BTreeMap::new(),
+        }
+    }
+}
+
+impl<T: Clone + Ord> FromIterator<(T, T)> for Order<T> {
+    fn from_iter<I: IntoIterator<Item = (T, T)>>(iter: I) -> Self {
+        let mut order = Order::default();
+        for (before, after) in iter {
+            order.add(before, after);
+        }
+        order
+    }
+}
+
+impl<T: Clone + Ord> Order<T> {
+    pub fn new() -> Self {
+        Order::default()
+    }
+
+    pub fn add(&mut self, before: T, after: T) {
+        self.nexts.entry(after.clone()).or_default();
+        self.prevs.entry(before.clone()).or_default();
+        self.nexts
+            .entry(before.clone())
+            .or_default()
+            .insert(after.clone());
+        self.prevs.entry(after).or_default().insert(before);
+    }
+
+    pub fn nodes(&self) -> impl Iterator<Item = &T> {
+        self.nexts.keys()
+    }
+
+    pub fn edges(&self) -> impl Iterator<Item = (&T, &T)> {
+        self.nexts
+            .iter()
+            .flat_map(|(before, afters)| afters.iter().map(move |after| (before, after)))
+    }
+
+    pub fn nexts(&self, node: &T) -> impl Iterator<Item = &T> {
+        self.nexts.get(node).into_iter().flatten()
+    }
+
+    pub fn prevs(&self, node: &T) -> impl Iterator<Item = &T> {
+        self.prevs.get(node).into_iter().flatten()
+    }
+
+    /// is there a rule directly saying `before` comes before `after`
+    pub fn is_before(&self, before: &T, after: &T) -> bool {
+        self.nexts.get(before).map(|afters| afters.contains(after)) == Some(true)
+    }
+
+    /// no item has a rule saying it should come before something earlier in `items`
+    pub fn is_in_order(&self, items: &[T]) -> bool {
+        items.iter().enumerate().all(|(i, after)| {
+            items[..i]
+                .iter()
+                .all(|before| !self.is_before(after, before))
+        })
+    }
+
+    /// just the rules between nodes in `items`
+    pub fn subset(&self, items: &[T]) -> Order<T> {
+        let wanted = items.iter().collect::<BTreeSet<_>>();
+        self.edges()
+            .filter(|(before, after)| wanted.contains(before) && wanted.contains(after))
+            .map(|(before, after)| (before.clone(), after.clone()))
+            .collect()
+    }
+
+    /// comparator for `sort_by` that's a proper total order over `items`, going by where each
+    /// one lands in a topological sort of them. rules through things that aren't in `items`
+    /// count too. errors if they make a cycle, and the comparator panics on anything that
+    /// wasn't in `items`. if the cycles are only through other nodes, take the `subset` first
+    pub fn comparator(&self, items: &[T]) -> Result<impl Fn(&T, &T) -> Ordering, Cycle<T>> {
+        let wanted = items.iter().cloned().collect::<BTreeSet<_>>();
+        let related = wanted
+            .iter()
+            .flat_map(|before| {
+                self.reachable(before)
+                    .into_iter()
+                    .filter(|after| wanted.contains(after))
+                    .map(move |after| (before.clone(), after))
+            })
+            .collect::<Order<_>>();
+
+        let rank = related
+            .topological_sort_subset(items)?
+            .into_iter()
+            .enumerate()
+            .map(|(rank, node)| (node, rank))
+            .collect::<BTreeMap<_, _>>();
+        Ok(move |a: &T, b: &T| rank[a].cmp(&rank[b]))
+    }
+
+    /// kahn's algorithm over every node
+    pub fn topological_sort(&self) -> Result<Vec<T>, Cycle<T>> {
+        let subset = self.nexts.keys().cloned().collect();
+        self.kahn(&subset)
+    }
+
+    /// kahn's algorithm over only the rules between nodes in `subset`. the full rule set may have
+    /// cycles while every subset you care about doesn't. duplicates in `subset` come out once
+    pub fn topological_sort_subset(&self, subset: &[T]) -> Result<Vec<T>, Cycle<T>> {
+        let subset = subset.iter().cloned().collect();
+        self.kahn(&subset)
+    }
+
+    fn kahn(&self, subset: &BTreeSet<T>) -> Result<Vec<T>, Cycle<T>> {
+        let mut in_degree = subset
+            .iter()
+            .map(|node| {
+                let degree = self
+                    .prevs(node)
+                    .filter(|prev| subset.contains(prev))
+                    .count();
+                (node.clone(), degree)
+            })
+            .collect::<BTreeMap<_, _>>();
+
+        let mut ready = in_degree
+            .iter()
+            .filter(|(_, degree)| **degree == 0)
+            .map(|(node, _)| node.clone())
+            .collect::<BTreeSet<_>>();
+
+        let mut sorted = Vec::with_capacity(subset.len());
+        while let Some(node) = ready.pop_first() {
+            for next in self.nexts(&node).filter(|next| subset.contains(next)) {
+                let degree = in_degree.get_mut(next).unwrap();
+                *degree -= 1;
+                if *degree == 0 {
+                    ready.insert(next.clone());
+                }
+            }
+            in_degree.remove(&node);
+            sorted.push(node);
+        }
+
+        if in_degree.is_empty() {
+            Ok(sorted)
+        } else {
+            Err(self.find_cycle(&in_degree))
+        }
+    }
+
+    /// everything left over after kahn's has a leftover predecessor, so walking backwards through
+    /// leftovers has to loop eventually
+    fn find_cycle(&self, leftover: &BTreeMap<T, usize>) -> Cycle<T> {
+        let mut walked = Vec::new();
+        let mut node = leftover.keys().next().unwrap().clone();
+        loop {
+            if let Some(start) = walked.iter().position(|walked| walked == &node) {
+                let mut cycle = walked.split_off(start);
+                cycle.reverse();
+                tracing::debug!("found cycle of length {}", cycle.len());
+                return Cycle(cycle);
+            }
+
+            let prev = self
+                .prevs(&node)
+                .find(|prev| leftover.contains_key(prev))
+                .unwrap()
+                .clone();
+            walked.push(node);
+            node = prev;
+        }
+    }
+
+    /// everything reachable from `node`, not including `node` unless it's on a cycle
+    pub fn reachable(&self, node: &T) -> BTreeSet<T> {
+        let mut reached = BTreeSet::new();
+        let mut stack = self.nexts(node).collect::<Vec<_>>();
+        while let Some(next) = stack.pop() {
+            if reached.insert(next.clone()) {
+                stack.extend(self.nexts(next));
+            }
+        }
+        reached
+    }
+
+    /// a rule for every pair where one has to come before the other
+    pub fn transitive_closure(&self) -> Order<T> {
+        let mut closure = Order::new();
+        for node in self.nodes() {
+            closure.nexts.entry(node.clone()).or_default();
+            closure.prevs.entry(node.clone()).or_default();
+            for after in self.reachable(node) {
+                closure.add(node.clone(), after);
+            }
+        }
+        closure
+    }
+
+    /// the fewest rules that still give the same closure. errors on cycles since then there's
+    /// more than one
+    pub fn transitive_reduction(&self) -> Result<Order<T>, Cycle<T>> {
+        self.topological_sort()?;
+
+        let mut reduction = Order::new();
+        for node in self.nodes() {
+            reduction.nexts.entry(node.clone()).or_default();
+            reduction.prevs.entry(node.clone()).or_default();
+
+            let indirect = self
+                .nexts(node)
+                .flat_map(|next| self.reachable(next))
+                .collect::<BTreeSet<_>>();
+            for next in self.nexts(node).filter(|next| !indirect.contains(next)) {
+                reduction.add(node.clone(), next.clone());
+            }
+        }
+        Ok(reduction)
+    }
+}
+
+#[test]
+fn sort() {
+    let order = Order::from_iter([(1, 2), (1, 3), (3, 2), (2, 4), (5, 4)]);
+    assert_eq!(order.topological_sort(), Ok(vec![1, 3, 2, 5, 4]));
+    assert_eq!(order.topological_sort_subset(&[4, 2, 5]), Ok(vec![2, 5, 4]));
+
+    let mut items = vec![4, 2, 3, 1, 2];
+    let comparator = order.comparator(&items).unwrap();
+    items.sort_by(comparator);
+    assert_eq!(items, vec![1, 3, 2, 2, 4]);
+    assert!(order.is_in_order(&items));
+    assert_eq!(order.topological_sort_subset(&items), Ok(vec![1, 3, 2, 4]));
+
+    // 3 isn't related to anything, and 1 only comes before 4 by way of 2
+    let order = Order::from_iter([(1, 2), (2, 4)]);
+    let mut items = vec![4, 3, 1];
+    let comparator = order.comparator(&items).unwrap();
+    items.sort_by(comparator);
+    assert_eq!(items, vec![1, 3, 4]);
+    assert!(Order::from_iter([(1, 2), (2, 1)])
+        .comparator(&[1, 2])
+        .is_err());
+
+    // the cycle goes through 3, so it's fine once that's left out
+    let order = Order::from_iter([(1, 2), (2, 3), (3, 1)]);
+    assert!(order.comparator(&[2, 1]).is_err());
+    let mut items = vec![2, 1];
+    let comparator = order.subset(&items).comparator(&items).unwrap();
+    items.sort_by(comparator);
+    assert_eq!(items, vec![1, 2]);
+}
+
+#[test]
+fn cycle() {
+    let order = Order::from_iter([(1, 2), (2, 3), (3, 4), (4, 2), (0, 1)]);
+    let Err(Cycle(cycle)) = order.topological_sort() else {
+        panic!("no cycle");
+    };
+    assert_eq!(cycle.len(), 3);
+    for i in 0..cycle.len() {
+        assert!(order.is_before(&cycle[i], &cycle[(i + 1) % cycle.len()]));
+    }
+
+    // no cycle without 4
+    assert_eq!(order.topological_sort_subset(&[3, 2, 1]), Ok(vec![1, 2, 3]));
+}
+
+#[test]
+fn closure_reduction() {
+    let order = Order::from_iter([(1, 2), (2, 3), (1, 3), (3, 4), (1, 4)]);
+    let closure = order.transitive_closure();
+    assert!(closure.is_before(&2, &4));
+    assert_eq!(closure.edges().count(), 6);
+
+    let reduction = order.transitive_reduction().unwrap();
+    assert_eq!(
+        reduction.edges().collect::<Vec<_>>(),
+        vec![(&1, &2), (&2, &3), (&3, &4)]
+    );
+}
@@ -0,0 +1,154 @@
+use super::{transform::Transform, Grid};
+
+/// a small picture to look for in a grid, where wildcard cells match anything
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Pattern {
+    cells: Grid<Option<char>>,
+}
+
+/// where a pattern turned up. `row` and `col` are its top left corner, after transforming it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Match {
+    pub row: usize,
+    pub col: usize,
+    pub transform: Transform,
+}
+
+impl Pattern {
+    /// `.` is the wildcard
+    pub fn new(template: &str) -> Self {
+        Pattern::with_wildcard(template, '.')
+    }
+
+    /// one row per line. short rows get filled out with wildcards
+    pub fn with_wildcard(template: &str, wildcard: char) -> Self {
+        let mut cells = template
+            .lines()
+            .map(|line| {
+                line.chars()
+                    .map(|c| if c == wildcard { None } else { Some(c) })
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Grid<_>>();
+
+        let width = cells.iter().map(Vec::len).max().unwrap_or(0);
+        for row in cells.iter_mut() {
+            row.resize(width, None);
+        }
+
+        Pattern { cells }
+    }
+
+    pub fn height(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn width(&self) -> usize {
+        self.cells.first().map_or(0, Vec::len)
+    }
+
+    pub fn transformed(&self, transform: Transform) -> Pattern {
+        Pattern {
+            cells: transform.apply(&self.cells),
+        }
+    }
+
+    /// every different way the pattern looks when rotated or flipped. symmetric patterns have
+    /// fewer than 8, so one spot in the grid can't match more than once
+    pub fn orientations(&self) -> Vec<(Transform, Pattern)> {
+        let mut orientations = Vec::<(Transform, Pattern)>::new();
+        for transform in Transform::ALL {
+            let pattern = self.transformed(transform);
+            if !orientations.iter().any(|(_, seen)| *seen == pattern) {
+                orientations.push((transform, pattern));
+            }
+        }
+        orientations
+    }
+
+    /// whether the pattern's top left corner can go at `row`,`col`
+    pub fn matches_at(&self, grid: &[Vec<char>], row: usize, col: usize) -> bool {
+        self.cells.iter().enumerate().all(|(pattern_row, cells)| {
+            grid.get(row + pattern_row).is_some_and(|lat| {
+                col + cells.len() <= lat.len()
+                    && cells
+                        .iter()
+                        .zip(&lat[col..])
+                        .all(|(cell, c)| cell.is_none_or(|cell| cell == *c))
+            })
+        })
+    }
+
+    /// top left corners of every match, in reading order
+    pub fn find<'a>(&'a self, grid: &'a [Vec<char>]) -> impl Iterator<Item = (usize, usize)> + 'a {
+        grid.iter().enumerate().flat_map(move |(row, lat)| {
+            (0..lat.len())
+                .filter(move |&col| self.matches_at(grid, row, col))
+                .map(move |col| (row, col))
+        })
+    }
+
+    /// matches of the pattern as it is
+    pub fn matches(&self, grid: &[Vec<char>]) -> Vec<Match> {
+        find_matches(&[(Transform::Identity, self.clone())], grid)
+    }
+}
+
+/// matches of any of the patterns, sorted by position
+pub fn find_matches(patterns: &[(Transform, Pattern)], grid: &[Vec<char>]) -> Vec<Match> {
+    let mut matches = patterns
+        .iter()
+        .flat_map(|(transform, pattern)| {
+            pattern.find(grid).map(|(row, col)| Match {
+                row,
+                col,
+                transform: *transform,
+            })
+        })
+        .collect::<Vec<_>>();
+    matches.sort();
+    matches
+}
+
+#[cfg(test)]
+fn chars(text: &str) -> Grid<char> {
+    text.lines().map(|line| line.chars().collect()).collect()
+}
+
+#[test]
+fn wildcards() {
+    let pattern = Pattern::new("#.#\n.#");
+    assert_eq!((pattern.height(), pattern.width()), (2, 3));
+
+    let grid = chars("#x#.\n.##.\n#o#x\n.#.#");
+    assert_eq!(
+        pattern.find(&grid).collect::<Vec<_>>(),
+        vec![(0, 0), (2, 0)]
+    );
+    assert!(!pattern.matches_at(&grid, 2, 1));
+    assert!(!pattern.matches_at(&grid, 3, 0));
+}
+
+#[test]
+fn orientations() {
+    let x_mas = Pattern::new("M.S\n.A.\nM.S");
+    assert_eq!(x_mas.orientations().len(), 4);
+    assert_eq!(Pattern::new("#.\n##").orientations().len(), 4);
+    assert_eq!(Pattern::new("##.\n.##").orientations().len(), 4);
+    assert_eq!(Pattern::new("#..\n###").orientations().len(), 8);
+
+    let grid = chars("S.S.S\n.A.A.\nM.M.M");
+    let found = find_matches(&x_mas.orientations(), &grid);
+    assert_eq!(
+        found
+            .iter()
+            .map(|found| (found.row, found.col))
+            .collect::<Vec<_>>(),
+        vec![(0, 0), (0, 2)]
+    );
+    assert_eq!(
+        x_mas.transformed(found[0].transform),
+        Pattern::new("S.S\n.A.\nM.M")
+    );
+    assert!(x_mas.matches(&grid).is_empty());
+}
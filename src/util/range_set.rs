@@ -0,0 +1,208 @@
+use num::PrimInt;
+use std::{collections::BTreeMap, ops::Range};
+
+/// a set of numbers stored as sorted half-open ranges that never touch or overlap, so it costs
+/// the same whether a range is 2 long or 2 billion
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RangeSet<T> {
+    /// start to end
+    ranges: BTreeMap<T, T>,
+}
+
+impl<T> Default for RangeSet<T> {
+    fn default() -> Self {
+        RangeSet {
+            ranges: BTreeMap::new(),
+        }
+    }
+}
+
+impl<T: Copy + Ord> RangeSet<T> {
+    pub fn new() -> Self {
+        RangeSet::default()
+    }
+
+    /// add `range`, merging it with anything it overlaps or touches
+    pub fn insert(&mut self, range: Range<T>) {
+        if range.is_empty() {
+            return;
+        }
+        let (mut start, mut end) = (range.start, range.end);
+
+        if let Some((&before_start, &before_end)) = self.ranges.range(..=start).next_back() {
+            if before_end >= start {
+                start = before_start;
+                end = end.max(before_end);
+            }
+        }
+        while let Some((&after_start, &after_end)) = self.ranges.range(start..=end).next() {
+            self.ranges.remove(&after_start);
+            end = end.max(after_end);
+        }
+
+        self.ranges.insert(start, end);
+    }
+
+    /// take `range` out, splitting whatever it lands in the middle of
+    pub fn remove(&mut self, range: Range<T>) {
+        if range.is_empty() {
+            return;
+        }
+
+        if let Some((&before_start, &before_end)) = self.ranges.range(..range.start).next_back() {
+            if before_end > range.start {
+                self.ranges.insert(before_start, range.start);
+                if before_end > range.end {
+                    self.ranges.insert(range.end, before_end);
+                }
+            }
+        }
+        while let Some((&inside_start, &inside_end)) =
+            self.ranges.range(range.start..range.end).next()
+        {
+            self.ranges.remove(&inside_start);
+            if inside_end > range.end {
+                self.ranges.insert(range.end, inside_end);
+            }
+        }
+    }
+
+    pub fn merge(&mut self, other: &RangeSet<T>) {
+        self.extend(other.iter());
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        self.ranges
+            .range(..=value)
+            .next_back()
+            .is_some_and(|(_, &end)| end > value)
+    }
+
+    /// the range `value` is in
+    pub fn range_of(&self, value: T) -> Option<Range<T>> {
+        self.ranges
+            .range(..=value)
+            .next_back()
+            .filter(|(_, &end)| end > value)
+            .map(|(&start, &end)| start..end)
+    }
+
+    /// number of separate ranges, not how many numbers are in them
+    pub fn len(&self) -> usize {
+        self.ranges.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = Range<T>> + '_ {
+        self.ranges.iter().map(|(&start, &end)| start..end)
+    }
+
+    /// the bits of `within` that aren't in the set, in order
+    pub fn gaps(&self, within: Range<T>) -> impl Iterator<Item = Range<T>> + '_ {
+        let mut cursor = within.start;
+        let first = self
+            .range_of(within.start)
+            .map(|range| range.start)
+            .unwrap_or(within.start);
+        let mut ranges = self.ranges.range(first..within.end.max(first));
+
+        std::iter::from_fn(move || loop {
+            if cursor >= within.end {
+                return None;
+            }
+            match ranges.next() {
+                Some((&start, &end)) => {
+                    let gap = cursor..start;
+                    cursor = cursor.max(end);
+                    if !gap.is_empty() {
+                        return Some(gap);
+                    }
+                }
+                None => {
+                    let gap = cursor..within.end;
+                    cursor = within.end;
+                    return Some(gap);
+                }
+            }
+        })
+    }
+}
+
+impl<T: PrimInt> RangeSet<T> {
+    /// how many numbers are in the set
+    pub fn size(&self) -> T {
+        self.ranges
+            .iter()
+            .fold(T::zero(), |size, (&start, &end)| size + (end - start))
+    }
+
+    /// the first gap in `within` that's at least `min_len` long
+    pub fn first_gap(&self, min_len: T, within: Range<T>) -> Option<Range<T>> {
+        self.gaps(within).find(|gap| gap.end - gap.start >= min_len)
+    }
+}
+
+impl<T: Copy + Ord> FromIterator<Range<T>> for RangeSet<T> {
+    fn from_iter<I: IntoIterator<Item = Range<T>>>(iter: I) -> Self {
+        let mut set = RangeSet::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl<T: Copy + Ord> Extend<Range<T>> for RangeSet<T> {
+    fn extend<I: IntoIterator<Item = Range<T>>>(&mut self, iter: I) {
+        for range in iter {
+            self.insert(range);
+        }
+    }
+}
+
+#[test]
+fn insert_remove() {
+    let mut set = [0..3, 5..7, 3..4, 10..12, 6..10]
+        .into_iter()
+        .collect::<RangeSet<u64>>();
+    assert_eq!(set.iter().collect::<Vec<_>>(), vec![0..4, 5..12]);
+    assert_eq!(set.size(), 11);
+
+    set.remove(2..6);
+    set.remove(8..9);
+    assert_eq!(set.iter().collect::<Vec<_>>(), vec![0..2, 6..8, 9..12]);
+    assert!(set.contains(9) && !set.contains(8) && !set.contains(12));
+    assert_eq!(set.range_of(7), Some(6..8));
+
+    set.remove(0..100);
+    assert!(set.is_empty());
+}
+
+#[test]
+fn gaps() {
+    let set = [2..4, 6..7, 10..15].into_iter().collect::<RangeSet<u64>>();
+    assert_eq!(
+        set.gaps(0..20).collect::<Vec<_>>(),
+        vec![0..2, 4..6, 7..10, 15..20]
+    );
+    assert_eq!(set.gaps(3..12).collect::<Vec<_>>(), vec![4..6, 7..10]);
+    assert_eq!(set.gaps(11..14).count(), 0);
+    assert_eq!(set.first_gap(3, 0..20), Some(7..10));
+    assert_eq!(set.first_gap(6, 0..20), None);
+}
+
+#[test]
+fn huge() {
+    let mut disk = RangeSet::new();
+    for i in 0..1000u64 {
+        disk.insert(i * 10_000_000..i * 10_000_000 + 9_999_999);
+    }
+    disk.insert(0..5_000_000_000);
+    assert_eq!(disk.len(), 500);
+    assert_eq!(
+        disk.first_gap(1, 0..u64::MAX),
+        Some(5_009_999_999..5_010_000_000)
+    );
+    assert_eq!(disk.size(), 5_000_000_000 + 500 * 9_999_999);
+}
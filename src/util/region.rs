@@ -0,0 +1,210 @@
+use super::{label_components, neighbourhood::Neighbourhood};
+use std::collections::BTreeSet;
+
+/// a bunch of cells, not necessarily connected, and the shape they make
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Region {
+    cells: BTreeSet<(usize, usize)>,
+}
+
+impl Region {
+    pub fn new(cells: impl IntoIterator<Item = (usize, usize)>) -> Self {
+        Region {
+            cells: cells.into_iter().collect(),
+        }
+    }
+
+    pub fn cells(&self) -> &BTreeSet<(usize, usize)> {
+        &self.cells
+    }
+
+    pub fn contains(&self, row: usize, col: usize) -> bool {
+        self.cells.contains(&(row, col))
+    }
+
+    /// negative and far away cells are never in the region
+    fn contains_offset(&self, (row, col): (usize, usize), row_off: isize, col_off: isize) -> bool {
+        match (
+            row.checked_add_signed(row_off),
+            col.checked_add_signed(col_off),
+        ) {
+            (Some(row), Some(col)) => self.contains(row, col),
+            _ => false,
+        }
+    }
+
+    pub fn area(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// which of a cell's neighbours are in the region too
+    pub fn neighbourhood(&self, cell: (usize, usize)) -> Neighbourhood {
+        Neighbourhood::from_fn(|direction| {
+            let (row_off, col_off) = direction.to_offset();
+            self.contains_offset(cell, row_off, col_off)
+        })
+    }
+
+    /// cell edges with something else on the other side
+    pub fn perimeter(&self) -> usize {
+        self.cells
+            .iter()
+            .map(|&cell| Neighbourhood::EDGES[self.neighbourhood(cell).0 as usize] as usize)
+            .sum()
+    }
+
+    /// number of straight sides, which is the same as the number of corners. see
+    /// `Neighbourhood::corners` for what counts, two cells touching only diagonally make two
+    /// corners there
+    pub fn sides(&self) -> usize {
+        self.cells
+            .iter()
+            .map(|&cell| Neighbourhood::CORNERS[self.neighbourhood(cell).0 as usize] as usize)
+            .sum()
+    }
+
+    /// top left and bottom right, inclusive. None if there aren't any cells
+    pub fn bounds(&self) -> Option<((usize, usize), (usize, usize))> {
+        let top = self.cells.first()?.0;
+        let bottom = self.cells.last()?.0;
+        let left = self.cells.iter().map(|&(_, col)| col).min()?;
+        let right = self.cells.iter().map(|&(_, col)| col).max()?;
+        Some(((top, left), (bottom, right)))
+    }
+
+    /// groups of cells that aren't in the region and can't get out of its bounding box without
+    /// crossing it, moving cardinally. something only touching the outside diagonally is a hole
+    pub fn holes(&self) -> Vec<Region> {
+        let Some(((top, left), (bottom, right))) = self.bounds() else {
+            return Vec::new();
+        };
+
+        // a ring of outside around the bounding box, so everything that isn't a hole joins up
+        let grid = (0..bottom - top + 3)
+            .map(|row| {
+                (0..right - left + 3)
+                    .map(|col| row > 0 && col > 0 && self.contains(row - 1 + top, col - 1 + left))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let components = label_components(&grid, |a, b| a == b);
+        let outside = components.label(0, 0);
+
+        (0..components.len())
+            .filter(|&label| {
+                let (row, col) = components.stats[label].first;
+                label != outside && !grid[row][col]
+            })
+            .map(|label| {
+                Region::new(
+                    components
+                        .cells(label)
+                        .map(|(row, col)| (row - 1 + top, col - 1 + left)),
+                )
+            })
+            .collect()
+    }
+}
+
+impl FromIterator<(usize, usize)> for Region {
+    fn from_iter<I: IntoIterator<Item = (usize, usize)>>(iter: I) -> Self {
+        Region::new(iter)
+    }
+}
+
+#[cfg(test)]
+fn region_from_mask(mask: u32, size: usize) -> Region {
+    (0..size * size)
+        .filter(|i| mask & (1 << i) != 0)
+        .map(|i| (i / size, i % size))
+        .collect()
+}
+
+/// every unit edge on the boundary, tagged with which way it faces, then joined up into runs
+#[cfg(test)]
+fn traced_edges(region: &Region) -> (usize, usize) {
+    let mut edges = BTreeSet::new();
+    for &(row, col) in region.cells() {
+        let (r, c) = (row as isize + 1, col as isize + 1);
+        let inside = |r: isize, c: isize| region.contains_offset((0, 0), r - 1, c - 1);
+        // (facing, the line the edge is on, where along it)
+        if !inside(r - 1, c) {
+            edges.insert(('N', r, c));
+        }
+        if !inside(r + 1, c) {
+            edges.insert(('S', r + 1, c));
+        }
+        if !inside(r, c - 1) {
+            edges.insert(('W', c, r));
+        }
+        if !inside(r, c + 1) {
+            edges.insert(('E', c + 1, r));
+        }
+    }
+
+    let runs = edges
+        .iter()
+        .filter(|&&(facing, line, along)| !edges.contains(&(facing, line, along - 1)))
+        .count();
+    (edges.len(), runs)
+}
+
+#[test]
+fn exhaustive_4x4() {
+    for mask in 0..1 << 16 {
+        let region = region_from_mask(mask, 4);
+        let (perimeter, sides) = traced_edges(&region);
+        assert_eq!(region.area(), mask.count_ones() as usize);
+        assert_eq!(region.perimeter(), perimeter, "perimeter of {:016b}", mask);
+        assert_eq!(region.sides(), sides, "sides of {:016b}", mask);
+    }
+}
+
+#[test]
+fn shapes() {
+    let lone = Region::new([(5, 5)]);
+    assert_eq!((lone.perimeter(), lone.sides()), (4, 4));
+    assert_eq!(lone.bounds(), Some(((5, 5), (5, 5))));
+
+    // the E from the puzzle
+    let e = Region::new(
+        ["EEEEE", "E....", "EEEEE", "E....", "EEEEE"]
+            .iter()
+            .enumerate()
+            .flat_map(|(row, line)| {
+                line.char_indices()
+                    .filter(|&(_, c)| c == 'E')
+                    .map(move |(col, _)| (row, col))
+            }),
+    );
+    assert_eq!((e.area(), e.sides()), (17, 12));
+    assert!(e.holes().is_empty());
+    assert_eq!(Region::default().bounds(), None);
+}
+
+#[test]
+fn holes() {
+    let ring = ["#####", "#..##", "##.##", "#####", "##.#.", "#.###"];
+    let region = ring
+        .iter()
+        .enumerate()
+        .flat_map(|(row, line)| {
+            line.char_indices()
+                .filter(|&(_, c)| c == '#')
+                .map(move |(col, _)| (row, col))
+        })
+        .collect::<Region>();
+
+    let holes = region.holes();
+    assert_eq!(holes.len(), 2);
+    assert_eq!(
+        holes[0].cells().iter().copied().collect::<Vec<_>>(),
+        vec![(1, 1), (1, 2), (2, 2)]
+    );
+    assert_eq!(
+        holes[1].cells().iter().copied().collect::<Vec<_>>(),
+        vec![(4, 2)]
+    );
+    // the gap in the bottom edge and the notch on the right are open to the outside
+    assert!(!holes.iter().any(|hole| hole.contains(5, 1)));
+}
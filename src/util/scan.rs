@@ -0,0 +1,282 @@
+use std::{
+    fmt::{Debug, Display, Formatter, Result as FmtResult},
+    str::FromStr,
+};
+
+/// where in the text scanning went wrong. line and column start at 1
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScanError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl ScanError {
+    fn at(text: &str, offset: usize, message: String) -> Self {
+        let before = &text[..offset];
+        let line = before.matches('\n').count() + 1;
+        let line_start = before.rfind('\n').map(|i| i + 1).unwrap_or(0);
+        ScanError {
+            line,
+            column: text[line_start..offset].chars().count() + 1,
+            message,
+        }
+    }
+
+    /// for when `text` was one line out of a bigger input. `line` starts at 1 like everything
+    /// else here
+    pub fn on_line(mut self, line: usize) -> Self {
+        assert!(line >= 1, "line numbers start at 1, got 0");
+        self.line += line - 1;
+        self
+    }
+}
+
+impl Display for ScanError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "{}:{}: {}", self.line, self.column, self.message)
+    }
+}
+
+impl std::error::Error for ScanError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Piece<'p> {
+    Literal(&'p str),
+    Capture,
+}
+
+/// split a pattern into literals and `{}`s. `{{` and `}}` are literal braces
+fn pieces<'p>(pattern: &'p str) -> Vec<Piece<'p>> {
+    let mut pieces = Vec::new();
+    let mut literal_start = 0;
+    let mut chars = pattern.char_indices().peekable();
+
+    let push_literal = |pieces: &mut Vec<Piece<'p>>, from: usize, to: usize| {
+        if from < to {
+            pieces.push(Piece::Literal(&pattern[from..to]));
+        }
+    };
+
+    while let Some((i, c)) = chars.next() {
+        match (c, chars.peek()) {
+            ('{', Some((_, '}'))) => {
+                push_literal(&mut pieces, literal_start, i);
+                pieces.push(Piece::Capture);
+                chars.next();
+                literal_start = i + 2;
+            }
+            ('{', Some((_, '{'))) | ('}', Some((_, '}'))) => {
+                // keep one of the pair
+                push_literal(&mut pieces, literal_start, i + 1);
+                chars.next();
+                literal_start = i + 2;
+            }
+            ('{' | '}', _) => panic!("unmatched {:?} at {} in pattern {:?}", c, i, pattern),
+            _ => {}
+        }
+    }
+    push_literal(&mut pieces, literal_start, pattern.len());
+
+    pieces
+}
+
+/// the `{}`s from a pattern, in order
+#[derive(Debug, Clone)]
+pub struct Captures<'t> {
+    text: &'t str,
+    spans: Vec<(usize, usize)>,
+}
+
+impl<'t> Captures<'t> {
+    pub fn len(&self) -> usize {
+        self.spans.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.spans.is_empty()
+    }
+
+    pub fn get(&self, index: usize) -> &'t str {
+        let (start, end) = self.spans[index];
+        &self.text[start..end]
+    }
+
+    pub fn parse<T: FromStr>(&self, index: usize) -> Result<T, ScanError> {
+        let (start, end) = self.spans[index];
+        parse_span(self.text, start, end)
+    }
+}
+
+fn parse_span<T: FromStr>(text: &str, start: usize, end: usize) -> Result<T, ScanError> {
+    text[start..end].parse().map_err(|_| {
+        ScanError::at(
+            text,
+            start,
+            format!(
+                "expected {}, found {:?}",
+                std::any::type_name::<T>(),
+                &text[start..end]
+            ),
+        )
+    })
+}
+
+/// match `text` against a pattern like `"Button A: X+{}, Y+{}"`. each `{}` takes everything up to
+/// the next bit of literal text, or the rest of the text if it's last. trailing whitespace in
+/// `text` is ignored
+pub fn captures<'t>(pattern: &str, text: &'t str) -> Result<Captures<'t>, ScanError> {
+    let pieces = pieces(pattern);
+    let mut spans = Vec::new();
+    let mut offset = 0;
+
+    for (i, piece) in pieces.iter().enumerate() {
+        match piece {
+            Piece::Literal(literal) => {
+                if !text[offset..].starts_with(literal) {
+                    return Err(ScanError::at(
+                        text,
+                        offset,
+                        format!("expected {:?}", literal),
+                    ));
+                }
+                offset += literal.len();
+            }
+
+            Piece::Capture => {
+                let end = match pieces.get(i + 1) {
+                    Some(Piece::Literal(literal)) => match text[offset..].find(literal) {
+                        Some(found) => offset + found,
+                        None => {
+                            return Err(ScanError::at(
+                                text,
+                                offset,
+                                format!("expected {:?} after capture", literal),
+                            ))
+                        }
+                    },
+                    Some(Piece::Capture) => panic!("two captures in a row in {:?}", pattern),
+                    None => text.trim_end().len().max(offset),
+                };
+
+                if end == offset {
+                    return Err(ScanError::at(text, offset, String::from("empty capture")));
+                }
+                spans.push((offset, end));
+                offset = end;
+            }
+        }
+    }
+
+    if !text[offset..].trim().is_empty() {
+        return Err(ScanError::at(
+            text,
+            offset,
+            format!("unexpected {:?}", &text[offset..]),
+        ));
+    }
+
+    Ok(Captures { text, spans })
+}
+
+/// `scan!(pattern, text => u64, i32)` gives a `Result<(u64, i32), ScanError>`
+macro_rules! scan {
+    ($pattern:expr, $text:expr => $($ty:ty),+ $(,)?) => {
+        $crate::util::scan::captures($pattern, $text).and_then(|captures| {
+            let mut _index = 0;
+            Ok(($({
+                _index += 1;
+                captures.parse::<$ty>(_index - 1)?
+            },)+))
+        })
+    };
+}
+
+pub(crate) use scan;
+
+fn integer_spans(text: &str, signed: bool) -> impl Iterator<Item = (usize, usize)> + '_ {
+    let bytes = text.as_bytes();
+    let mut i = 0;
+    std::iter::from_fn(move || {
+        while i < bytes.len() {
+            let negative = signed
+                && bytes[i] == b'-'
+                && bytes.get(i + 1).is_some_and(|next| next.is_ascii_digit());
+            if bytes[i].is_ascii_digit() || negative {
+                let start = i;
+                i += 1;
+                while i < bytes.len() && bytes[i].is_ascii_digit() {
+                    i += 1;
+                }
+                return Some((start, i));
+            }
+            i += 1;
+        }
+        None
+    })
+}
+
+/// every integer in `text`, where a `-` right before digits makes it negative. errors say
+/// where a number didn't fit in `T`
+pub fn integers<T: FromStr>(text: &str) -> impl Iterator<Item = Result<T, ScanError>> + '_ {
+    integer_spans(text, true).map(|(start, end)| parse_span(text, start, end))
+}
+
+/// every run of digits in `text`, ignoring any `-`
+pub fn naturals<T: FromStr>(text: &str) -> impl Iterator<Item = Result<T, ScanError>> + '_ {
+    integer_spans(text, false).map(|(start, end)| parse_span(text, start, end))
+}
+
+#[test]
+fn typed() {
+    let parsed = scan!("Button A: X+{}, Y+{}", "Button A: X+94, Y+34" => u64, i32);
+    assert_eq!(parsed, Ok((94, 34)));
+
+    let parsed = scan!("{}: {} {{{}}}", "x: true {-3}\n" => String, bool, i8);
+    assert_eq!(parsed, Ok((String::from("x"), true, -3)));
+}
+
+#[test]
+fn positioned_errors() {
+    let text = "Prize: X=8400, Y=5400\nPrize: X=12, Y=nope";
+    let err = captures("Prize: X={}, Y={}\nPrize: X={}, Y={}", text)
+        .unwrap()
+        .parse::<u64>(3)
+        .unwrap_err();
+    assert_eq!((err.line, err.column), (2, 16));
+
+    let err = captures("p={},{} v={},{}", "p=0,4 V=3,-3").unwrap_err();
+    assert_eq!((err.line, err.column), (1, 5));
+    assert_eq!(
+        err.on_line(10).to_string(),
+        "10:5: expected \" v=\" after capture"
+    );
+}
+
+#[test]
+#[should_panic(expected = "line numbers start at 1")]
+fn line_zero() {
+    captures("x={}", "y=1").unwrap_err().on_line(0);
+}
+
+#[test]
+fn numbers() {
+    let line = "p=0,-4 v=3--3 x-y 12";
+    assert_eq!(
+        integers::<i64>(line).collect::<Result<Vec<_>, _>>(),
+        Ok(vec![0, -4, 3, -3, 12])
+    );
+    assert_eq!(
+        naturals::<u64>(line).collect::<Result<Vec<_>, _>>(),
+        Ok(vec![0, 4, 3, 3, 12])
+    );
+
+    let err = integers::<i32>("x=1\ny=99999999999")
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap_err();
+    assert_eq!((err.line, err.column), (2, 3));
+    assert_eq!(
+        naturals::<u8>("-300").next().unwrap().unwrap_err().column,
+        2
+    );
+}
@@ -0,0 +1,341 @@
+use super::FastSet;
+use num::Zero;
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, VecDeque},
+    hash::{BuildHasher, Hash},
+    ops::Add,
+};
+
+/// result of a search from `start`. `prev` holds every predecessor that lies on some shortest
+/// path, so all of the shortest paths can be reconstructed, not just one. hashed with fnv unless
+/// it came from `bfs_with_hasher`
+#[derive(Debug, Clone)]
+pub struct Search<S, C, H = fnv::FnvBuildHasher> {
+    pub start: S,
+    pub cost: HashMap<S, C, H>,
+    pub prev: HashMap<S, Vec<S>, H>,
+    pub goal: Option<S>,
+}
+
+impl<S: Clone + Eq + Hash, C: Copy, H: BuildHasher + Default> Search<S, C, H> {
+    fn new(start: S, zero: C) -> Self {
+        Search {
+            cost: HashMap::from_iter([(start.clone(), zero)]),
+            prev: HashMap::default(),
+            start,
+            goal: None,
+        }
+    }
+
+    pub fn cost_to(&self, state: &S) -> Option<C> {
+        self.cost.get(state).copied()
+    }
+
+    pub fn reached(&self, state: &S) -> bool {
+        self.cost.contains_key(state)
+    }
+
+    /// one shortest path, start and end inclusive
+    pub fn path_to(&self, end: &S) -> Option<Vec<S>> {
+        if !self.reached(end) {
+            return None;
+        }
+
+        let mut path = vec![end.clone()];
+        while let Some(prev) = self.prev.get(path.last().unwrap()) {
+            path.push(prev[0].clone());
+        }
+        path.reverse();
+
+        Some(path)
+    }
+
+    /// every shortest path, start and end inclusive
+    pub fn all_paths_to(&self, end: &S) -> Vec<Vec<S>> {
+        if !self.reached(end) {
+            return Vec::new();
+        }
+
+        let mut paths = Vec::new();
+        let mut stack = vec![vec![end.clone()]];
+        while let Some(path) = stack.pop() {
+            match self.prev.get(path.last().unwrap()) {
+                Some(prevs) => {
+                    for prev in prevs {
+                        let mut path = path.clone();
+                        path.push(prev.clone());
+                        stack.push(path);
+                    }
+                }
+                None => {
+                    let mut path = path;
+                    path.reverse();
+                    paths.push(path);
+                }
+            }
+        }
+
+        paths
+    }
+
+    /// number of shortest paths without building them. goes depth first with its own stack, so
+    /// long paths are fine
+    pub fn count_paths_to(&self, end: &S) -> usize {
+        if !self.reached(end) {
+            return 0;
+        }
+
+        let mut counts = HashMap::<S, usize, H>::default();
+        let mut stack = vec![end.clone()];
+        while let Some(state) = stack.last().cloned() {
+            if counts.contains_key(&state) {
+                stack.pop();
+                continue;
+            }
+
+            let Some(prevs) = self.prev.get(&state) else {
+                counts.insert(state, 1);
+                stack.pop();
+                continue;
+            };
+
+            // count everything before it first, then come back
+            let pending = prevs
+                .iter()
+                .filter(|prev| !counts.contains_key(prev))
+                .cloned()
+                .collect::<Vec<_>>();
+            if pending.is_empty() {
+                let n = prevs.iter().map(|prev| counts[prev]).sum();
+                counts.insert(state, n);
+                stack.pop();
+            } else {
+                stack.extend(pending);
+            }
+        }
+
+        counts[end]
+    }
+}
+
+/// breadth-first search from `start`, visiting everything reachable through `neighbors`
+pub fn bfs<S, I>(start: S, neighbors: impl FnMut(&S) -> I) -> Search<S, usize>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = S>,
+{
+    bfs_with_hasher(start, neighbors)
+}
+
+/// `bfs` with the maps hashed by `H`
+pub fn bfs_with_hasher<S, I, H>(start: S, mut neighbors: impl FnMut(&S) -> I) -> Search<S, usize, H>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = S>,
+    H: BuildHasher + Default,
+{
+    let mut search = Search::new(start.clone(), 0);
+
+    let mut queue = VecDeque::from([start]);
+    while let Some(state) = queue.pop_front() {
+        let next_cost = search.cost[&state] + 1;
+        for next in neighbors(&state) {
+            match search.cost.get(&next) {
+                Some(&cost) if cost == next_cost => {
+                    search.prev.get_mut(&next).unwrap().push(state.clone());
+                }
+                Some(_) => {}
+                None => {
+                    search.cost.insert(next.clone(), next_cost);
+                    search.prev.insert(next.clone(), vec![state.clone()]);
+                    queue.push_back(next);
+                }
+            }
+        }
+    }
+
+    search
+}
+
+/// dijkstra's algorithm from `start`, stopping once a state satisfying `is_goal` is settled. pass
+/// `|_| false` to get costs for everything reachable. zero cost edges are fine, cycles of them
+/// included
+pub fn dijkstra<S, C, I>(
+    start: S,
+    neighbors: impl FnMut(&S) -> I,
+    is_goal: impl FnMut(&S) -> bool,
+) -> Search<S, C>
+where
+    S: Clone + Eq + Hash,
+    C: Copy + Ord + Add<Output = C> + Zero,
+    I: IntoIterator<Item = (S, C)>,
+{
+    astar(start, neighbors, |_| C::zero(), is_goal)
+}
+
+/// A* from `start` towards a state satisfying `is_goal`. `heuristic` should never overestimate
+/// the remaining cost, and should be consistent if the returned costs are to be exact for states
+/// other than the goal. edges back into a state that's already settled are ignored, so `prev`
+/// never loops back on itself
+pub fn astar<S, C, I>(
+    start: S,
+    mut neighbors: impl FnMut(&S) -> I,
+    mut heuristic: impl FnMut(&S) -> C,
+    mut is_goal: impl FnMut(&S) -> bool,
+) -> Search<S, C>
+where
+    S: Clone + Eq + Hash,
+    C: Copy + Ord + Add<Output = C> + Zero,
+    I: IntoIterator<Item = (S, C)>,
+{
+    let mut search = Search::new(start.clone(), C::zero());
+
+    // states live in a vec so the heap doesn't need S: Ord
+    let mut states = vec![start.clone()];
+    let mut heap = BinaryHeap::from([(Reverse(heuristic(&start)), Reverse(C::zero()), 0)]);
+    let mut settled = FastSet::default();
+
+    while let Some((_, Reverse(cost), index)) = heap.pop() {
+        let state = states[index].clone();
+        if search.cost[&state] < cost || !settled.insert(state.clone()) {
+            tracing::trace!("stale heap entry");
+            continue;
+        }
+
+        if is_goal(&state) {
+            search.goal = Some(state);
+            break;
+        }
+
+        for (next, step) in neighbors(&state) {
+            // its cost is already final, and a zero cost edge back could make `prev` loop
+            if settled.contains(&next) {
+                continue;
+            }
+
+            let next_cost = cost + step;
+            match search.cost.get(&next) {
+                Some(&known) if known < next_cost => {}
+                Some(&known) if known == next_cost => {
+                    search.prev.get_mut(&next).unwrap().push(state.clone());
+                }
+                _ => {
+                    search.cost.insert(next.clone(), next_cost);
+                    search.prev.insert(next.clone(), vec![state.clone()]);
+                    heap.push((
+                        Reverse(next_cost + heuristic(&next)),
+                        Reverse(next_cost),
+                        states.len(),
+                    ));
+                    states.push(next);
+                }
+            }
+        }
+    }
+
+    search
+}
+
+#[cfg(test)]
+fn grid_neighbors(plane: &[Vec<char>]) -> impl FnMut(&(usize, usize)) -> Vec<(usize, usize)> + '_ {
+    |&(row, col)| {
+        super::surrounding_cardinal(plane, row, col)
+            .filter(|(c, _, _, _)| **c != '#')
+            .map(|(_, row, col, _)| (row, col))
+            .collect()
+    }
+}
+
+#[test]
+fn bfs_grid() {
+    let plane: Vec<Vec<char>> = ["...#", ".#..", "...#", "#..."]
+        .iter()
+        .map(|line| line.chars().collect())
+        .collect();
+
+    let search = bfs((0, 0), grid_neighbors(&plane));
+    assert_eq!(search.cost_to(&(3, 3)), Some(6));
+    assert_eq!(search.cost_to(&(0, 3)), None);
+    assert_eq!(search.path_to(&(3, 3)).unwrap().len(), 7);
+    assert_eq!(search.all_paths_to(&(2, 2)).len(), 2);
+    assert_eq!(search.count_paths_to(&(3, 3)), 3);
+}
+
+#[test]
+fn weighted_agree() {
+    let plane: Vec<Vec<char>> = ["....#...", ".##.#.#.", ".#....#.", "...##...", ".#......"]
+        .iter()
+        .map(|line| line.chars().collect())
+        .collect();
+    let goal = (4, 7);
+
+    let mut neighbors = grid_neighbors(&plane);
+    let weighted = |state: &(usize, usize)| {
+        neighbors(state)
+            .into_iter()
+            .map(|next| (next, 1usize))
+            .collect::<Vec<_>>()
+    };
+    let d = dijkstra((0, 0), weighted, |state| *state == goal);
+
+    let mut neighbors = grid_neighbors(&plane);
+    let weighted = |state: &(usize, usize)| {
+        neighbors(state)
+            .into_iter()
+            .map(|next| (next, 1usize))
+            .collect::<Vec<_>>()
+    };
+    let manhattan = |&(row, col): &(usize, usize)| goal.0.abs_diff(row) + goal.1.abs_diff(col);
+    let a = astar((0, 0), weighted, manhattan, |state| *state == goal);
+
+    let b = bfs((0, 0), grid_neighbors(&plane));
+
+    assert_eq!(d.goal, Some(goal));
+    assert_eq!(a.goal, Some(goal));
+    assert_eq!(d.cost_to(&goal), b.cost_to(&goal));
+    assert_eq!(a.cost_to(&goal), b.cost_to(&goal));
+    assert_eq!(a.count_paths_to(&goal), b.count_paths_to(&goal));
+    assert_eq!(d.all_paths_to(&goal).len(), b.all_paths_to(&goal).len());
+}
+
+#[test]
+fn zero_cost_edges() {
+    let neighbors = |&n: &u32| match n {
+        0 => vec![(1, 0), (2, 1)],
+        1 => vec![(0, 0), (2, 1)],
+        _ => vec![],
+    };
+    let search = dijkstra(0, neighbors, |_| false);
+    assert_eq!(search.cost_to(&1), Some(0));
+    assert_eq!(search.cost_to(&2), Some(1));
+    assert_eq!(search.path_to(&0), Some(vec![0]));
+    assert_eq!(search.count_paths_to(&2), 2);
+    assert_eq!(search.all_paths_to(&2).len(), 2);
+}
+
+#[test]
+fn zero_cost_cycle() {
+    let neighbors = |&n: &char| match n {
+        'S' => vec![('A', 1)],
+        'A' => vec![('B', 0)],
+        'B' => vec![('A', 0)],
+        _ => vec![],
+    };
+    let search = dijkstra('S', neighbors, |_| false);
+    assert_eq!(search.cost_to(&'B'), Some(1));
+    assert_eq!(search.prev[&'A'], vec!['S']);
+    assert_eq!(search.count_paths_to(&'A'), 1);
+    assert_eq!(search.all_paths_to(&'B'), vec![vec!['S', 'A', 'B']]);
+}
+
+#[test]
+fn long_chain() {
+    let end = 200_000;
+    let search = bfs(0, |&n: &u32| if n < end { vec![n + 1] } else { vec![] });
+    assert_eq!(search.count_paths_to(&end), 1);
+    assert_eq!(
+        search.path_to(&end).map(|path| path.len()),
+        Some(end as usize + 1)
+    );
+}
@@ -0,0 +1,173 @@
+//! a grid with no edges, for when things wander off the plane or into negative coordinates
+
+use super::{Direction, FastMap};
+
+/// a map from signed `(row, col)` to values, where anything never set is `default`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SparseGrid<T> {
+    cells: FastMap<(isize, isize), T>,
+    default: T,
+    /// top left and bottom right of everything that's been set, inclusive
+    bounds: Option<((isize, isize), (isize, isize))>,
+}
+
+impl<T> SparseGrid<T> {
+    pub fn new(default: T) -> Self {
+        SparseGrid {
+            cells: FastMap::default(),
+            default,
+            bounds: None,
+        }
+    }
+
+    /// every cell of `plane` that isn't the default, at the same positions
+    pub fn from_plane(plane: &[Vec<T>], default: T) -> Self
+    where
+        T: Clone + PartialEq,
+    {
+        let mut grid = SparseGrid::new(default);
+        for (row, lat) in plane.iter().enumerate() {
+            for (col, value) in lat.iter().enumerate() {
+                if *value != grid.default {
+                    grid.insert(row as isize, col as isize, value.clone());
+                }
+            }
+        }
+        grid
+    }
+
+    pub fn default_value(&self) -> &T {
+        &self.default
+    }
+
+    pub fn get(&self, row: isize, col: isize) -> &T {
+        self.cells.get(&(row, col)).unwrap_or(&self.default)
+    }
+
+    /// sets it to a copy of the default first if it wasn't set
+    pub fn get_mut(&mut self, row: isize, col: isize) -> &mut T
+    where
+        T: Clone,
+    {
+        self.grow(row, col);
+        self.cells
+            .entry((row, col))
+            .or_insert_with(|| self.default.clone())
+    }
+
+    pub fn contains(&self, row: isize, col: isize) -> bool {
+        self.cells.contains_key(&(row, col))
+    }
+
+    /// the old value if it was set
+    pub fn insert(&mut self, row: isize, col: isize, value: T) -> Option<T> {
+        self.grow(row, col);
+        self.cells.insert((row, col), value)
+    }
+
+    /// back to the default. the bounds shrink if it was on the edge of them
+    pub fn remove(&mut self, row: isize, col: isize) -> Option<T> {
+        let old = self.cells.remove(&(row, col))?;
+        if let Some(((top, left), (bottom, right))) = self.bounds {
+            if row == top || row == bottom || col == left || col == right {
+                self.bounds = None;
+                let positions = self.cells.keys().copied().collect::<Vec<_>>();
+                for (row, col) in positions {
+                    self.grow(row, col);
+                }
+            }
+        }
+        Some(old)
+    }
+
+    fn grow(&mut self, row: isize, col: isize) {
+        self.bounds = Some(match self.bounds {
+            None => ((row, col), (row, col)),
+            Some(((top, left), (bottom, right))) => (
+                (top.min(row), left.min(col)),
+                (bottom.max(row), right.max(col)),
+            ),
+        });
+    }
+
+    /// top left and bottom right of everything that's been set, inclusive. None if nothing is
+    pub fn bounds(&self) -> Option<((isize, isize), (isize, isize))> {
+        self.bounds
+    }
+
+    /// number of cells that have been set
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// cells that have been set, in no particular order
+    pub fn iter(&self) -> impl Iterator<Item = ((isize, isize), &T)> + '_ {
+        self.cells
+            .iter()
+            .map(|(&position, value)| (position, value))
+    }
+
+    /// the neighbours in `directions`, set or not, since there's no edge to fall off
+    pub fn neighbours<'a>(
+        &'a self,
+        row: isize,
+        col: isize,
+        directions: &'a [Direction],
+    ) -> impl Iterator<Item = (&'a T, isize, isize, Direction)> + 'a {
+        directions.iter().map(move |&direction| {
+            let (row_off, col_off) = direction.to_offset();
+            let (row, col) = (row + row_off, col + col_off);
+            (self.get(row, col), row, col, direction)
+        })
+    }
+
+    /// one line per row of the bounding box, with `f` picking each cell's character
+    pub fn render(&self, mut f: impl FnMut(&T) -> char) -> String {
+        let Some(((top, left), (bottom, right))) = self.bounds else {
+            return String::new();
+        };
+
+        let mut text = String::new();
+        for row in top..=bottom {
+            text.extend((left..=right).map(|col| f(self.get(row, col))));
+            text.push('\n');
+        }
+        text
+    }
+}
+
+#[test]
+fn sparse() {
+    let mut grid = SparseGrid::new('.');
+    assert_eq!(grid.render(|&c| c), "");
+    grid.insert(-2, 3, '#');
+    grid.insert(1, -1, '#');
+    *grid.get_mut(0, 0) = 'o';
+    assert_eq!(grid.bounds(), Some(((-2, -1), (1, 3))));
+    assert_eq!(grid.render(|&c| c), "....#\n.....\n.o...\n#....\n");
+    assert_eq!(*grid.get(100, -100), '.');
+
+    assert_eq!(grid.remove(-2, 3), Some('#'));
+    assert_eq!(grid.remove(-2, 3), None);
+    assert_eq!(grid.bounds(), Some(((0, -1), (1, 0))));
+    assert_eq!(grid.len(), 2);
+}
+
+#[test]
+fn neighbours() {
+    let plane = vec![vec![0, 1], vec![2, 0]];
+    let grid = SparseGrid::from_plane(&plane, 0);
+    assert_eq!(grid.len(), 2);
+
+    let around = grid
+        .neighbours(0, 0, &Direction::ALL)
+        .filter(|(&value, ..)| value != 0)
+        .map(|(&value, row, col, _)| (value, row, col))
+        .collect::<Vec<_>>();
+    assert_eq!(around, vec![(1, 0, 1), (2, 1, 0)]);
+    assert_eq!(grid.neighbours(-5, -5, &Direction::CARDINAL).count(), 4);
+}
@@ -0,0 +1,132 @@
+//! grids where going off one edge brings you back on the opposite one. everything wraps with
+//! euclidean modulo, so it doesn't matter how far off the edge something ends up
+
+use super::{Direction, SurroundingItem};
+
+/// `value` brought back into `0..len`
+pub fn wrap(value: isize, len: usize) -> usize {
+    assert!(len > 0, "can't wrap {} around nothing", value);
+    value.rem_euclid(len as isize) as usize
+}
+
+/// where something at `position` moving `velocity` per step is after `steps` steps, on a
+/// `height` by `width` torus. doesn't step through, so any number of steps is as quick
+pub fn advance(
+    (row, col): (usize, usize),
+    (row_vel, col_vel): (isize, isize),
+    steps: u64,
+    (height, width): (usize, usize),
+) -> (usize, usize) {
+    assert!(height > 0 && width > 0, "empty torus");
+    let along = |at: usize, vel: isize, len: usize| {
+        (at as i128 + vel as i128 * steps as i128).rem_euclid(len as i128) as usize
+    };
+    (along(row, row_vel, height), along(col, col_vel, width))
+}
+
+/// a plane looked at as a torus. it's assumed to be rectangular, going by the first row
+#[derive(Debug, Clone, Copy)]
+pub struct Torus<'a, T> {
+    plane: &'a [Vec<T>],
+    height: usize,
+    width: usize,
+}
+
+impl<'a, T> Torus<'a, T> {
+    pub fn new(plane: &'a [Vec<T>]) -> Self {
+        let (height, width) = (plane.len(), plane.first().map_or(0, Vec::len));
+        assert!(height > 0 && width > 0, "empty torus");
+        Torus {
+            plane,
+            height,
+            width,
+        }
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// the cell any position lands on
+    pub fn wrap(&self, row: isize, col: isize) -> (usize, usize) {
+        (wrap(row, self.height), wrap(col, self.width))
+    }
+
+    pub fn get(&self, row: isize, col: isize) -> &'a T {
+        let (row, col) = self.wrap(row, col);
+        &self.plane[row][col]
+    }
+
+    /// one step `direction`, never falling off
+    pub fn step(&self, (row, col): (usize, usize), direction: Direction) -> (usize, usize) {
+        let (row_off, col_off) = direction.to_offset();
+        self.wrap(row as isize + row_off, col as isize + col_off)
+    }
+
+    pub fn advance(
+        &self,
+        position: (usize, usize),
+        velocity: (isize, isize),
+        steps: u64,
+    ) -> (usize, usize) {
+        advance(position, velocity, steps, (self.height, self.width))
+    }
+
+    /// every neighbour in `directions`, like `surrounding` but round the edges
+    pub fn surrounding(
+        &self,
+        row: usize,
+        col: usize,
+        directions: &'static [Direction],
+    ) -> impl Iterator<Item = SurroundingItem<'a, T>> + '_ {
+        directions.iter().map(move |&direction| {
+            let (row, col) = self.step((row, col), direction);
+            (&self.plane[row][col], row, col, direction)
+        })
+    }
+}
+
+#[test]
+fn wrapping() {
+    assert_eq!(wrap(-1, 5), 4);
+    assert_eq!(wrap(-11, 5), 4);
+    assert_eq!(wrap(12, 5), 2);
+
+    let plane = vec![vec![1, 2, 3], vec![4, 5, 6]];
+    let torus = Torus::new(&plane);
+    assert_eq!(*torus.get(-1, -1), 6);
+    assert_eq!(torus.step((0, 0), Direction::NW), (1, 2));
+    assert_eq!(
+        torus
+            .surrounding(0, 0, &Direction::CARDINAL)
+            .map(|(&value, ..)| value)
+            .collect::<Vec<_>>(),
+        vec![4, 4, 2, 3]
+    );
+    assert_eq!(Direction::W.apply_index_wrapping(&plane, 1, 0), (1, 2));
+}
+
+#[test]
+fn advancing() {
+    let size = (7, 11);
+    let velocity = (-3, 5);
+    let mut position = (4, 2);
+    for steps in 0..200 {
+        assert_eq!(advance((4, 2), velocity, steps, size), position);
+        position = (
+            wrap(position.0 as isize + velocity.0, size.0),
+            wrap(position.1 as isize + velocity.1, size.1),
+        );
+    }
+    assert_eq!(
+        advance((0, 0), (isize::MIN, isize::MAX), u64::MAX, (101, 103)),
+        (
+            (isize::MIN as i128 * u64::MAX as i128).rem_euclid(101) as usize,
+            (isize::MAX as i128 * u64::MAX as i128).rem_euclid(103) as usize
+        )
+    );
+}
@@ -0,0 +1,194 @@
+//! turning grids around and reading them along straight lines. grids are assumed to be
+//! rectangular, going by the length of the first row
+
+use super::Grid;
+
+fn width<T>(grid: &[Vec<T>]) -> usize {
+    grid.first().map_or(0, Vec::len)
+}
+
+/// number of diagonals going either way
+fn diagonal_count(height: usize, width: usize) -> usize {
+    if height == 0 || width == 0 {
+        0
+    } else {
+        height + width - 1
+    }
+}
+
+/// rows become columns, so `transpose(grid)[col][row] == grid[row][col]`
+pub fn transpose<T: Clone>(grid: &[Vec<T>]) -> Grid<T> {
+    (0..width(grid))
+        .map(|col| grid.iter().map(|row| row[col].clone()).collect())
+        .collect()
+}
+
+/// clockwise, so the left column becomes the top row
+pub fn rotate_90<T: Clone>(grid: &[Vec<T>]) -> Grid<T> {
+    (0..width(grid))
+        .map(|col| grid.iter().rev().map(|row| row[col].clone()).collect())
+        .collect()
+}
+
+pub fn rotate_180<T: Clone>(grid: &[Vec<T>]) -> Grid<T> {
+    grid.iter()
+        .rev()
+        .map(|row| row.iter().rev().cloned().collect())
+        .collect()
+}
+
+/// anticlockwise 90
+pub fn rotate_270<T: Clone>(grid: &[Vec<T>]) -> Grid<T> {
+    (0..width(grid))
+        .rev()
+        .map(|col| grid.iter().map(|row| row[col].clone()).collect())
+        .collect()
+}
+
+/// mirror left to right
+pub fn flip_horizontal<T: Clone>(grid: &[Vec<T>]) -> Grid<T> {
+    grid.iter()
+        .map(|row| row.iter().rev().cloned().collect())
+        .collect()
+}
+
+/// mirror top to bottom
+pub fn flip_vertical<T: Clone>(grid: &[Vec<T>]) -> Grid<T> {
+    grid.iter().rev().cloned().collect()
+}
+
+/// mirror across the diagonal from top right to bottom left
+pub fn anti_transpose<T: Clone>(grid: &[Vec<T>]) -> Grid<T> {
+    rotate_180(&transpose(grid))
+}
+
+/// the eight ways to rotate and flip a grid
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Transform {
+    Identity,
+    Rotate90,
+    Rotate180,
+    Rotate270,
+    FlipHorizontal,
+    FlipVertical,
+    Transpose,
+    AntiTranspose,
+}
+
+impl Transform {
+    pub const ALL: [Transform; 8] = [
+        Transform::Identity,
+        Transform::Rotate90,
+        Transform::Rotate180,
+        Transform::Rotate270,
+        Transform::FlipHorizontal,
+        Transform::FlipVertical,
+        Transform::Transpose,
+        Transform::AntiTranspose,
+    ];
+
+    pub fn apply<T: Clone>(&self, grid: &[Vec<T>]) -> Grid<T> {
+        match self {
+            Transform::Identity => grid.to_vec(),
+            Transform::Rotate90 => rotate_90(grid),
+            Transform::Rotate180 => rotate_180(grid),
+            Transform::Rotate270 => rotate_270(grid),
+            Transform::FlipHorizontal => flip_horizontal(grid),
+            Transform::FlipVertical => flip_vertical(grid),
+            Transform::Transpose => transpose(grid),
+            Transform::AntiTranspose => anti_transpose(grid),
+        }
+    }
+}
+
+pub fn rows<T>(grid: &[Vec<T>]) -> impl Iterator<Item = &[T]> {
+    grid.iter().map(Vec::as_slice)
+}
+
+/// top to bottom, left column first
+pub fn columns<T>(grid: &[Vec<T>]) -> impl Iterator<Item = Vec<&T>> {
+    (0..width(grid)).map(move |col| grid.iter().map(|row| &row[col]).collect())
+}
+
+/// lines going down and right, starting from the bottom left corner and ending at the top right
+pub fn diagonals<T>(grid: &[Vec<T>]) -> impl Iterator<Item = Vec<&T>> {
+    let (height, width) = (grid.len(), width(grid));
+    (0..diagonal_count(height, width)).map(move |diagonal| {
+        let row = (height - 1).saturating_sub(diagonal);
+        let col = diagonal.saturating_sub(height - 1);
+        (0..(height - row).min(width - col))
+            .map(|i| &grid[row + i][col + i])
+            .collect()
+    })
+}
+
+/// lines going down and left, starting from the top left corner and ending at the bottom right
+pub fn anti_diagonals<T>(grid: &[Vec<T>]) -> impl Iterator<Item = Vec<&T>> {
+    let (height, width) = (grid.len(), width(grid));
+    (0..diagonal_count(height, width)).map(move |anti_diagonal| {
+        let row = anti_diagonal.saturating_sub(width - 1);
+        let col = anti_diagonal - row;
+        (0..(height - row).min(col + 1))
+            .map(|i| &grid[row + i][col - i])
+            .collect()
+    })
+}
+
+/// every row, column, diagonal and anti-diagonal as a string, each read one way. reverse them
+/// (or the thing being looked for) to get the other four directions
+pub fn line_strings(grid: &[Vec<char>]) -> impl Iterator<Item = String> + '_ {
+    rows(grid)
+        .map(|row| row.iter().collect())
+        .chain(columns(grid).map(|line| line.into_iter().collect()))
+        .chain(diagonals(grid).map(|line| line.into_iter().collect()))
+        .chain(anti_diagonals(grid).map(|line| line.into_iter().collect()))
+}
+
+#[cfg(test)]
+fn chars(lines: &[&str]) -> Grid<char> {
+    lines.iter().map(|line| line.chars().collect()).collect()
+}
+
+#[test]
+fn transforms() {
+    let grid = chars(&["abc", "def"]);
+    assert_eq!(transpose(&grid), chars(&["ad", "be", "cf"]));
+    assert_eq!(rotate_90(&grid), chars(&["da", "eb", "fc"]));
+    assert_eq!(rotate_180(&grid), chars(&["fed", "cba"]));
+    assert_eq!(rotate_270(&grid), chars(&["cf", "be", "ad"]));
+    assert_eq!(flip_horizontal(&grid), chars(&["cba", "fed"]));
+    assert_eq!(flip_vertical(&grid), chars(&["def", "abc"]));
+
+    assert_eq!(rotate_90(&rotate_90(&grid)), rotate_180(&grid));
+    assert_eq!(rotate_90(&rotate_270(&grid)), grid);
+    assert_eq!(transpose(&grid), rotate_90(&flip_vertical(&grid)));
+    assert_eq!(anti_transpose(&grid), chars(&["fc", "eb", "da"]));
+
+    let mut all = Transform::ALL.map(|transform| transform.apply(&grid));
+    all.sort();
+    all.windows(2).for_each(|pair| assert_ne!(pair[0], pair[1]));
+}
+
+#[test]
+fn lines() {
+    let grid = chars(&["abc", "def"]);
+    let strings = |lines: Vec<Vec<&char>>| {
+        lines
+            .into_iter()
+            .map(|line| line.into_iter().collect::<String>())
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(strings(columns(&grid).collect()), vec!["ad", "be", "cf"]);
+    assert_eq!(
+        strings(diagonals(&grid).collect()),
+        vec!["d", "ae", "bf", "c"]
+    );
+    assert_eq!(
+        strings(anti_diagonals(&grid).collect()),
+        vec!["a", "bd", "ce", "f"]
+    );
+    assert_eq!(line_strings(&grid).count(), 2 + 3 + 4 + 4);
+    assert_eq!(diagonals(&Grid::<char>::new()).count(), 0);
+    assert_eq!(anti_diagonals(&vec![Vec::<char>::new(); 3]).count(), 0);
+}